@@ -0,0 +1,76 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use aoc2022lib::{
+    collections::FastBuildHasher,
+    points::{Point2D, Point3D},
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Like the rock and sand units in d14 and d17
+fn points_2d() -> Vec<Point2D<i32>> {
+    (0..200)
+        .flat_map(|x| (0..200).map(move |y| Point2D(x * 3 - 300, y * 7 - 700)))
+        .collect()
+}
+
+/// Like the droplet cubes in d18
+fn points_3d() -> Vec<Point3D<i8>> {
+    (0..20)
+        .flat_map(|x| (0..20).flat_map(move |y| (0..20).map(move |z| Point3D(x, y, z))))
+        .collect()
+}
+
+fn fill_and_query_set<T, S>(points: &[T]) -> usize
+where
+    T: Hash + Eq + Copy,
+    S: BuildHasher + Default,
+{
+    let set: HashSet<T, S> = points.iter().copied().collect();
+    points.iter().filter(|point| set.contains(point)).count()
+}
+
+fn fill_and_query_map<T, S>(points: &[T]) -> usize
+where
+    T: Hash + Eq + Copy,
+    S: BuildHasher + Default,
+{
+    let map: HashMap<T, usize, S> = points.iter().copied().zip(0..).collect();
+    points.iter().map(|point| map[point]).sum()
+}
+
+fn sets(c: &mut Criterion) {
+    let mut group = c.benchmark_group("set");
+    let points_2d = points_2d();
+    let points_3d = points_3d();
+    group.bench_with_input(BenchmarkId::new("std", "2d"), &points_2d, |b, points| {
+        b.iter(|| fill_and_query_set::<_, RandomState>(black_box(points)));
+    });
+    group.bench_with_input(BenchmarkId::new("fast", "2d"), &points_2d, |b, points| {
+        b.iter(|| fill_and_query_set::<_, FastBuildHasher>(black_box(points)));
+    });
+    group.bench_with_input(BenchmarkId::new("std", "3d"), &points_3d, |b, points| {
+        b.iter(|| fill_and_query_set::<_, RandomState>(black_box(points)));
+    });
+    group.bench_with_input(BenchmarkId::new("fast", "3d"), &points_3d, |b, points| {
+        b.iter(|| fill_and_query_set::<_, FastBuildHasher>(black_box(points)));
+    });
+    group.finish();
+}
+
+fn maps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("map");
+    let points_2d = points_2d();
+    group.bench_with_input(BenchmarkId::new("std", "2d"), &points_2d, |b, points| {
+        b.iter(|| fill_and_query_map::<_, RandomState>(black_box(points)));
+    });
+    group.bench_with_input(BenchmarkId::new("fast", "2d"), &points_2d, |b, points| {
+        b.iter(|| fill_and_query_map::<_, FastBuildHasher>(black_box(points)));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sets, maps);
+criterion_main!(benches);
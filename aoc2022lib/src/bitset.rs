@@ -0,0 +1,284 @@
+use std::ops::{BitAnd, BitOr, Sub};
+
+/// Storage for a [`BitSet`]: a fixed number of bits, all zero at first
+pub trait Bits: Copy + Eq {
+    const CAPACITY: usize;
+    const EMPTY: Self;
+
+    fn get(&self, idx: usize) -> bool;
+    fn set(&mut self, idx: usize, value: bool);
+    #[must_use]
+    fn or(self, other: Self) -> Self;
+    #[must_use]
+    fn and(self, other: Self) -> Self;
+    #[must_use]
+    fn and_not(self, other: Self) -> Self;
+    fn count_ones(&self) -> u32;
+    /// The index of the lowest bit set, if any
+    fn lowest(&self) -> Option<usize>;
+}
+
+macro_rules! impl_bits {
+    ($($t:ty),+) => {
+        $(
+            impl Bits for $t {
+                const CAPACITY: usize = <$t>::BITS as usize;
+                const EMPTY: Self = 0;
+
+                fn get(&self, idx: usize) -> bool {
+                    (*self >> idx) & 1 == 1
+                }
+
+                fn set(&mut self, idx: usize, value: bool) {
+                    if value {
+                        *self |= 1 << idx;
+                    } else {
+                        *self &= !(1 << idx);
+                    }
+                }
+
+                fn or(self, other: Self) -> Self {
+                    self | other
+                }
+
+                fn and(self, other: Self) -> Self {
+                    self & other
+                }
+
+                fn and_not(self, other: Self) -> Self {
+                    self & !other
+                }
+
+                fn count_ones(&self) -> u32 {
+                    <$t>::count_ones(*self)
+                }
+
+                fn lowest(&self) -> Option<usize> {
+                    (*self != 0).then(|| self.trailing_zeros() as usize)
+                }
+            }
+        )+
+    };
+}
+
+impl_bits!(u64, u128);
+
+impl<const N: usize> Bits for [u64; N] {
+    const CAPACITY: usize = N * 64;
+    const EMPTY: Self = [0; N];
+
+    fn get(&self, idx: usize) -> bool {
+        self[idx / 64].get(idx % 64)
+    }
+
+    fn set(&mut self, idx: usize, value: bool) {
+        self[idx / 64].set(idx % 64, value);
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        self.iter_mut()
+            .zip(other)
+            .for_each(|(word, other)| *word |= other);
+        self
+    }
+
+    fn and(mut self, other: Self) -> Self {
+        self.iter_mut()
+            .zip(other)
+            .for_each(|(word, other)| *word &= other);
+        self
+    }
+
+    fn and_not(mut self, other: Self) -> Self {
+        self.iter_mut()
+            .zip(other)
+            .for_each(|(word, other)| *word &= !other);
+        self
+    }
+
+    fn count_ones(&self) -> u32 {
+        self.iter().map(|word| word.count_ones()).sum()
+    }
+
+    fn lowest(&self) -> Option<usize> {
+        self.iter()
+            .enumerate()
+            .find_map(|(word_idx, word)| Some(word_idx * 64 + word.lowest()?))
+    }
+}
+
+/// A set of small integers, each below the capacity of `B`
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct BitSet<B = u64>(B);
+
+impl<B: Bits> BitSet<B> {
+    pub fn new() -> Self {
+        Self(B::EMPTY)
+    }
+
+    /// Maps each char to the element it stands for
+    pub fn from_chars_with(
+        chars: impl IntoIterator<Item = char>,
+        mapping: impl FnMut(char) -> usize,
+    ) -> Self {
+        chars.into_iter().map(mapping).collect()
+    }
+
+    /// Letters, mapped through [`letter_index`]. `None` if there's anything else among them
+    pub fn try_from_letters(letters: impl IntoIterator<Item = char>) -> Option<Self> {
+        letters.into_iter().map(letter_index).collect()
+    }
+
+    /// Whether it wasn't there yet
+    pub fn insert(&mut self, element: usize) -> bool {
+        assert!(element < B::CAPACITY, "{element} doesn't fit in the set");
+        let is_new = !self.0.get(element);
+        self.0.set(element, true);
+        is_new
+    }
+
+    /// Whether it was there
+    pub fn remove(&mut self, element: usize) -> bool {
+        let was_there = self.contains(element);
+        if was_there {
+            self.0.set(element, false);
+        }
+        was_there
+    }
+
+    pub fn contains(&self, element: usize) -> bool {
+        element < B::CAPACITY && self.0.get(element)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == B::EMPTY
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0.or(other.0))
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0.and(other.0))
+    }
+
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0.and_not(other.0))
+    }
+
+    /// In increasing order
+    pub fn iter(&self) -> Iter<B> {
+        Iter(self.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Iter<B>(B);
+
+impl<B: Bits> Iterator for Iter<B> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lowest = self.0.lowest()?;
+        self.0.set(lowest, false);
+        Some(lowest)
+    }
+}
+
+impl<B: Bits> Default for BitSet<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Bits> BitOr for BitSet<B> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(&rhs)
+    }
+}
+
+impl<B: Bits> BitAnd for BitSet<B> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(&rhs)
+    }
+}
+
+impl<B: Bits> Sub for BitSet<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(&rhs)
+    }
+}
+
+impl<B: Bits> FromIterator<usize> for BitSet<B> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<B: Bits> Extend<usize> for BitSet<B> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+/// `a..=z` to `1..=26` and `A..=Z` to `27..=52`, same as the rucksack item priorities.
+/// `None` for anything but an ASCII letter
+pub fn letter_index(letter: char) -> Option<usize> {
+    match letter {
+        'a'..='z' => Some(letter as usize - 'a' as usize + 1),
+        'A'..='Z' => Some(letter as usize - 'A' as usize + 27),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let a: BitSet = BitSet::try_from_letters("vJrwpWtwJgWr".chars()).unwrap();
+        let b: BitSet = BitSet::try_from_letters("hcsFMMfFFhFp".chars()).unwrap();
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [16]);
+        assert_eq!((a | b).len(), a.len() + b.len() - 1);
+        assert!(!(a - b).contains(16));
+        assert_eq!(BitSet::<u64>::try_from_letters("ab1".chars()), None);
+    }
+
+    #[test]
+    fn u128_and_array_storage() {
+        let mut small = BitSet::<u128>::new();
+        assert!(small.insert(100));
+        assert!(!small.insert(100));
+        assert!(small.remove(100));
+        assert!(small.is_empty());
+
+        let big: BitSet<[u64; 4]> = [3, 64, 200, 255].into_iter().collect();
+        assert_eq!(big.iter().collect::<Vec<_>>(), [3, 64, 200, 255]);
+        assert_eq!(big.len(), 4);
+        assert!(!big.contains(256));
+    }
+
+    #[test]
+    fn custom_mapping() {
+        let digits = BitSet::<u64>::from_chars_with("4041".chars(), |c| c as usize - '0' as usize);
+        assert_eq!(digits.iter().collect::<Vec<_>>(), [0, 1, 4]);
+    }
+}
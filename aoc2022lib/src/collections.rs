@@ -0,0 +1,23 @@
+use std::collections::{HashMap, HashSet};
+
+/// Much faster than the default hasher for small keys like points.
+/// It isn't randomly seeded either, so the iteration order is the same on every run
+pub type FastBuildHasher = rustc_hash::FxBuildHasher;
+
+/// A [`HashMap`] using [`FastBuildHasher`]
+pub type FastMap<K, V> = HashMap<K, V, FastBuildHasher>;
+
+/// A [`HashSet`] using [`FastBuildHasher`]
+pub type FastSet<T> = HashSet<T, FastBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iteration_order_is_reproducible() {
+        let first: FastSet<u32> = (0..1000).map(|n| n * 7919).collect();
+        let second: FastSet<u32> = (0..1000).map(|n| n * 7919).collect();
+        assert!(first.iter().eq(second.iter()));
+    }
+}
@@ -1,12 +1,23 @@
 use std::str::FromStr;
 
-pub fn p1(file: &str) -> anyhow::Result<u32> {
+pub fn parse(file: &str) -> aoc2022lib::Result<&str> {
+    Ok(file)
+}
+
+pub fn part1(_file: &str) -> aoc2022lib::Result<u32> {
     todo!()
 }
-pub fn p2(_file: &str) -> anyhow::Result<u32> {
+pub fn part2(_file: &str) -> aoc2022lib::Result<u32> {
     todo!()
 }
 
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(parse(file)?)
+}
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(parse(file)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,125 @@
+use std::{convert::Infallible, fmt::Display, io, num::TryFromIntError};
+
+use crate::{ocr::OcrError, parse::ParseError};
+
+/// Everything that can go wrong solving a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    /// The input doesn't follow the puzzle's format
+    Parse(ParseError),
+    /// A part of the input the puzzle needs isn't there
+    MissingInput(String),
+    /// The input is fine, but there's no answer to it
+    NoSolution,
+    /// The input follows the format, but breaks one of the puzzle's rules
+    InvalidInput(String),
+    /// A number got too big for its type
+    Overflow,
+    /// The input couldn't be read
+    Io(String),
+}
+
+pub type Result<T, E = AocError> = std::result::Result<T, E>;
+
+impl AocError {
+    pub fn missing_input(what: impl Display) -> Self {
+        Self::MissingInput(what.to_string())
+    }
+
+    pub fn invalid_input(reason: impl Display) -> Self {
+        Self::InvalidInput(reason.to_string())
+    }
+
+    /// See [`ParseError::on_line`], other errors aren't tied to a location
+    #[must_use]
+    pub fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::Parse(err) => Self::Parse(err.on_line(line)),
+            err => err,
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::MissingInput(what) => write!(f, "missing input: {what}"),
+            Self::NoSolution => write!(f, "the input has no solution"),
+            Self::InvalidInput(reason) => write!(f, "invalid input: {reason}"),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+            Self::Io(reason) => write!(f, "failed to read the input: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// For simulations that can't fail, see [`crate::simulation::Simulation::Error`]
+impl From<Infallible> for AocError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<nom::error::Error<String>> for AocError {
+    fn from(err: nom::error::Error<String>) -> Self {
+        Self::Parse(err.into())
+    }
+}
+
+impl From<TryFromIntError> for AocError {
+    fn from(_: TryFromIntError) -> Self {
+        Self::Overflow
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
+
+impl From<OcrError> for AocError {
+    fn from(err: OcrError) -> Self {
+        Self::invalid_input(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(
+            AocError::from(u8::try_from(256).unwrap_err()),
+            AocError::Overflow
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            AocError::missing_input("rearrangements").to_string(),
+            "missing input: rearrangements"
+        );
+        assert_eq!(
+            AocError::Parse(ParseError::at("ab\ncd", "d", "expected a digit")).to_string(),
+            "line 2, column 2: failed to parse \"d\": expected a digit"
+        );
+    }
+}
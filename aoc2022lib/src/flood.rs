@@ -0,0 +1,172 @@
+use std::{
+    collections::VecDeque,
+    hash::Hash,
+    ops::{Add, Sub},
+};
+
+use crate::{
+    collections::FastSet,
+    points::{Bounds2D, Bounds3D, Point2D, Point3D},
+};
+
+/// Which points count as touching each other
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Connectivity {
+    /// Sharing a side: 4 neighbours in 2D, 6 in 3D
+    Orthogonal,
+    /// Sharing at least a corner: 8 neighbours in 2D, 26 in 3D
+    WithDiagonals,
+}
+
+/// A point that can be flooded from, along with the kind of bounds its regions have
+pub trait GridPoint: Copy + Eq + Hash {
+    type Bounds;
+
+    fn adjacent(&self, connectivity: Connectivity) -> Vec<Self>;
+
+    /// `None` if there are no points
+    fn bounds(points: impl IntoIterator<Item = Self>) -> Option<Self::Bounds>;
+}
+
+impl<T> GridPoint for Point2D<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + From<i8> + Ord + Hash + Copy,
+{
+    type Bounds = Bounds2D<T>;
+
+    fn adjacent(&self, connectivity: Connectivity) -> Vec<Self> {
+        match connectivity {
+            Connectivity::Orthogonal => self.neighbours().to_vec(),
+            Connectivity::WithDiagonals => self.neighbours_with_diagonals().to_vec(),
+        }
+    }
+
+    fn bounds(points: impl IntoIterator<Item = Self>) -> Option<Self::Bounds> {
+        Bounds2D::from_points(points)
+    }
+}
+
+impl<T> GridPoint for Point3D<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + From<i8> + Ord + Hash + Copy,
+{
+    type Bounds = Bounds3D<T>;
+
+    fn adjacent(&self, connectivity: Connectivity) -> Vec<Self> {
+        match connectivity {
+            Connectivity::Orthogonal => self.neighbours().to_vec(),
+            Connectivity::WithDiagonals => self.neighbours_with_diagonals().to_vec(),
+        }
+    }
+
+    fn bounds(points: impl IntoIterator<Item = Self>) -> Option<Self::Bounds> {
+        Bounds3D::from_points(points)
+    }
+}
+
+/// All the points reachable from `seed` through points satisfying `is_open`, including the seed.
+/// `is_open` has to reject everything outside some bounds, otherwise this never ends
+pub fn flood_fill<P: GridPoint>(
+    seed: P,
+    connectivity: Connectivity,
+    mut is_open: impl FnMut(&P) -> bool,
+) -> FastSet<P> {
+    let mut filled = FastSet::from_iter([seed]);
+    let mut queue = VecDeque::from([seed]);
+    while let Some(point) = queue.pop_front() {
+        for neighbour in point.adjacent(connectivity) {
+            if !filled.contains(&neighbour) && is_open(&neighbour) {
+                filled.insert(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    filled
+}
+
+/// A set of points that are all connected to each other
+#[derive(Clone, Debug)]
+pub struct Component<P> {
+    pub points: FastSet<P>,
+}
+
+impl<P: GridPoint> Component<P> {
+    pub fn size(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn contains(&self, point: &P) -> bool {
+        self.points.contains(point)
+    }
+
+    pub fn bounds(&self) -> P::Bounds {
+        P::bounds(self.points.iter().copied()).expect("components are never empty")
+    }
+}
+
+/// Splits `points` into the groups that touch each other, largest first
+pub fn components<P: GridPoint>(
+    points: impl IntoIterator<Item = P>,
+    connectivity: Connectivity,
+) -> Vec<Component<P>> {
+    let mut unvisited: FastSet<P> = points.into_iter().collect();
+    let mut components = Vec::new();
+    while let Some(&seed) = unvisited.iter().next() {
+        let points = flood_fill(seed, connectivity, |point| unvisited.contains(point));
+        for point in &points {
+            unvisited.remove(point);
+        }
+        components.push(Component { points });
+    }
+    components.sort_by_key(|component| std::cmp::Reverse(component.size()));
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_flood_fill() {
+        let walls = [Point2D(1, 0), Point2D(1, 1), Point2D(0, 1)];
+        let bounds = Bounds2D::new(Point2D(0, 0), Point2D(3, 3));
+        let is_open = |point: &Point2D<i32>| bounds.contains(point) && !walls.contains(point);
+        assert_eq!(
+            flood_fill(Point2D(0, 0), Connectivity::Orthogonal, is_open).len(),
+            1
+        );
+        assert_eq!(
+            flood_fill(Point2D(3, 3), Connectivity::Orthogonal, is_open).len(),
+            12
+        );
+    }
+
+    #[test]
+    fn components_2d() {
+        let points = [Point2D(0, 0), Point2D(1, 1), Point2D(2, 1), Point2D(5, 5)];
+        let orthogonal = components(points, Connectivity::Orthogonal);
+        assert_eq!(
+            orthogonal.iter().map(Component::size).collect::<Vec<_>>(),
+            [2, 1, 1]
+        );
+        assert_eq!(
+            orthogonal[0].bounds(),
+            Bounds2D::new(Point2D(1, 1), Point2D(2, 1))
+        );
+        let with_diagonals = components(points, Connectivity::WithDiagonals);
+        assert_eq!(with_diagonals.len(), 2);
+        assert_eq!(with_diagonals[0].size(), 3);
+    }
+
+    #[test]
+    fn components_3d() {
+        let points = [Point3D(0i8, 0, 0), Point3D(1, 1, 1), Point3D(1, 1, 2)];
+        assert_eq!(components(points, Connectivity::Orthogonal).len(), 2);
+        let with_diagonals = components(points, Connectivity::WithDiagonals);
+        assert_eq!(with_diagonals.len(), 1);
+        assert_eq!(
+            with_diagonals[0].bounds(),
+            Bounds3D::new(Point3D(0, 0, 0), Point3D(1, 1, 2))
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::collections::FastMap;
+
+/// A graph whose edges all cost the same, given by a function from each node to its neighbours.
+/// The nodes may carry extra state, e.g. the time for searches through a changing map
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    fn neighbours(&self, node: &Self::Node) -> impl IntoIterator<Item = Self::Node>;
+}
+
+/// Like [`Graph`], but every edge has its own cost
+pub trait WeightedGraph {
+    type Node: Clone + Eq + Hash;
+    type Cost: Copy + Ord + Default + Add<Output = Self::Cost>;
+
+    fn edges(&self, node: &Self::Node) -> impl IntoIterator<Item = (Self::Node, Self::Cost)>;
+}
+
+/// What a search found out about the graph
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    /// Distances of all the nodes reached, from the closest start
+    pub distances: FastMap<N, C>,
+    /// The node each one was reached from, so starts have none
+    pub predecessors: FastMap<N, N>,
+    /// The goal the search stopped at, if any
+    pub goal: Option<N>,
+    /// How many nodes had their edges looked at
+    pub num_expanded: usize,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new() -> Self {
+        Self {
+            distances: FastMap::default(),
+            predecessors: FastMap::default(),
+            goal: None,
+            num_expanded: 0,
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn goal_distance(&self) -> Option<C> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    /// From the start it was reached from to the node itself
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(predecessor) = self.predecessors.get(path.last()?) {
+            path.push(predecessor.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Breadth-first search from all of `starts` at once, until a node satisfying `is_goal` is reached.
+/// Pass `|_| false` to get the distances to every reachable node
+pub fn bfs<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+) -> Search<G::Node, usize> {
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        search.num_expanded += 1;
+        let distance = search.distances[&node] + 1;
+        for neighbour in graph.neighbours(&node) {
+            if let Entry::Vacant(entry) = search.distances.entry(neighbour.clone()) {
+                entry.insert(distance);
+                search.predecessors.insert(neighbour.clone(), node.clone());
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm from all of `starts` at once, until a node satisfying `is_goal` is reached.
+/// Pass `|_| false` to get the distances to every reachable node
+pub fn dijkstra<G: WeightedGraph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    is_goal: impl FnMut(&G::Node) -> bool,
+) -> Search<G::Node, G::Cost> {
+    astar(graph, starts, |_| G::Cost::default(), is_goal)
+}
+
+/// A* from all of `starts` at once, until a node satisfying `is_goal` is reached.
+/// `heuristic` must never overestimate the remaining distance to the closest goal
+pub fn astar<G: WeightedGraph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    mut heuristic: impl FnMut(&G::Node) -> G::Cost,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+) -> Search<G::Node, G::Cost> {
+    let mut search = Search::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if search
+            .distances
+            .insert(start.clone(), G::Cost::default())
+            .is_none()
+        {
+            queue.push(Candidate {
+                priority: heuristic(&start),
+                distance: G::Cost::default(),
+                node: start,
+            });
+        }
+    }
+
+    while let Some(Candidate { distance, node, .. }) = queue.pop() {
+        // a shorter way to the node was found after this one had been queued
+        if search.distances[&node] < distance {
+            continue;
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        search.num_expanded += 1;
+        for (neighbour, cost) in graph.edges(&node) {
+            let new_distance = distance + cost;
+            let is_shorter = search
+                .distances
+                .get(&neighbour)
+                .is_none_or(|&old_distance| new_distance < old_distance);
+            if is_shorter {
+                search.distances.insert(neighbour.clone(), new_distance);
+                search.predecessors.insert(neighbour.clone(), node.clone());
+                queue.push(Candidate {
+                    priority: new_distance + heuristic(&neighbour),
+                    distance: new_distance,
+                    node: neighbour,
+                });
+            }
+        }
+    }
+    search
+}
+
+/// A queued node, the one with the lowest `priority` comes out of the heap first
+struct Candidate<N, C> {
+    priority: C,
+    distance: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Candidate<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Candidate<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Candidate<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Candidate<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::points::Point2D;
+
+    /// `#` are walls, digits are the cost of stepping on a tile
+    struct Maze(Vec<&'static [u8]>);
+
+    impl Maze {
+        fn tile(&self, Point2D(x, y): Point2D<usize>) -> Option<u8> {
+            self.0.get(y)?.get(x).copied().filter(|&tile| tile != b'#')
+        }
+    }
+
+    impl Graph for Maze {
+        type Node = Point2D<usize>;
+
+        fn neighbours(&self, node: &Self::Node) -> impl IntoIterator<Item = Self::Node> {
+            node.checked_neighbours()
+                .filter(|&neighbour| self.tile(neighbour).is_some())
+        }
+    }
+
+    impl WeightedGraph for Maze {
+        type Node = Point2D<usize>;
+        type Cost = u32;
+
+        fn edges(&self, node: &Self::Node) -> impl IntoIterator<Item = (Self::Node, u32)> {
+            Graph::neighbours(self, node)
+                .into_iter()
+                .map(|neighbour| (neighbour, u32::from(self.tile(neighbour).unwrap() - b'0')))
+        }
+    }
+
+    fn maze() -> Maze {
+        Maze(vec![b"1111", b"1##9", b"1111"])
+    }
+
+    #[test]
+    fn bfs_path() {
+        let maze = maze();
+        let goal = Point2D(3, 2);
+        let search = bfs(&maze, [Point2D(0, 0)], |node| *node == goal);
+        assert_eq!(search.goal_distance(), Some(5));
+        let path = search.goal_path().unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!((path[0], path[5]), (Point2D(0, 0), goal));
+    }
+
+    #[test]
+    fn bfs_multi_source_distance_map() {
+        let maze = maze();
+        let search = bfs(&maze, [Point2D(0, 0), Point2D(3, 2)], |_| false);
+        assert_eq!(search.distances.len(), 10);
+        assert_eq!(search.distance(&Point2D(3, 1)), Some(1));
+        assert_eq!(search.distance(&Point2D(0, 2)), Some(2));
+        assert_eq!(search.goal, None);
+    }
+
+    #[test]
+    fn weighted_searches_avoid_the_expensive_tile() {
+        let maze = maze();
+        let goal = Point2D(3, 2);
+        let dijkstra = dijkstra(&maze, [Point2D(3, 0)], |node| *node == goal);
+        assert_eq!(dijkstra.goal_distance(), Some(8));
+        let astar = astar(
+            &maze,
+            [Point2D(3, 0)],
+            |node| u32::try_from(node.manhattan(goal)).unwrap(),
+            |node| *node == goal,
+        );
+        assert_eq!(astar.goal_distance(), Some(8));
+        assert!(astar.num_expanded <= dijkstra.num_expanded);
+        assert!(!astar.goal_path().unwrap().contains(&Point2D(3, 1)));
+    }
+}
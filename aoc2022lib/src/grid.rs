@@ -0,0 +1,189 @@
+use std::{
+    collections::hash_map,
+    hash::Hash,
+    ops::{Add, RangeInclusive, Sub},
+};
+
+use crate::{
+    collections::FastMap,
+    points::{Bounds2D, Point2D},
+    render::Canvas,
+};
+
+/// Cells at arbitrary, possibly negative, coordinates, keeping track of the rectangle around them
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T, C = i32> {
+    cells: FastMap<Point2D<C>, T>,
+    bounds: Option<Bounds2D<C>>,
+}
+
+impl<T, C: Ord + Hash + Copy> SparseGrid<T, C> {
+    pub fn new() -> Self {
+        Self {
+            cells: FastMap::default(),
+            bounds: None,
+        }
+    }
+
+    /// The previous value of the cell, if any
+    pub fn insert(&mut self, point: Point2D<C>, value: T) -> Option<T> {
+        let point_bounds = Bounds2D::new(point, point);
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.union(&point_bounds),
+            None => point_bounds,
+        });
+        self.cells.insert(point, value)
+    }
+
+    /// Doesn't shrink the bounds, see [`SparseGrid::shrink_bounds`]
+    pub fn remove(&mut self, point: &Point2D<C>) -> Option<T> {
+        self.cells.remove(point)
+    }
+
+    /// Keeps the cells `f` returns `true` for, and shrinks the bounds to them
+    pub fn retain(&mut self, f: impl FnMut(&Point2D<C>, &mut T) -> bool) {
+        self.cells.retain(f);
+        self.shrink_bounds();
+    }
+
+    /// Recalculates the bounds after cells have been removed
+    pub fn shrink_bounds(&mut self) {
+        self.bounds = Bounds2D::from_points(self.cells.keys().copied());
+    }
+
+    pub fn get(&self, point: &Point2D<C>) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &Point2D<C>) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    pub fn contains(&self, point: &Point2D<C>) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Around all the cells inserted since the last shrinking, `None` if there are none
+    pub fn bounds(&self) -> Option<Bounds2D<C>> {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Point2D<C>, T> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point2D<C>> + '_ {
+        self.cells.keys().copied()
+    }
+}
+
+impl<T, C: Ord + Hash + Copy> Default for SparseGrid<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Ord + Hash + Copy> Extend<(Point2D<C>, T)> for SparseGrid<T, C> {
+    fn extend<I: IntoIterator<Item = (Point2D<C>, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+impl<T, C: Ord + Hash + Copy> FromIterator<(Point2D<C>, T)> for SparseGrid<T, C> {
+    fn from_iter<I: IntoIterator<Item = (Point2D<C>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+/// A grid used as a set of points
+impl<C: Ord + Hash + Copy> FromIterator<Point2D<C>> for SparseGrid<(), C> {
+    fn from_iter<I: IntoIterator<Item = Point2D<C>>>(iter: I) -> Self {
+        iter.into_iter().map(|point| (point, ())).collect()
+    }
+}
+
+/// How [`SparseGrid::render_with`] and [`Canvas::from_grid`] lay the cells out
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderOptions<C> {
+    /// Empty cells added on every side
+    pub padding: C,
+    /// What to show instead of the grid's own bounds
+    pub viewport: Option<Bounds2D<C>>,
+    /// Draw the highest `y` at the top, instead of the lowest
+    pub y_up: bool,
+}
+
+impl<T, C> SparseGrid<T, C>
+where
+    C: Add<Output = C> + Sub<Output = C> + Ord + Hash + Copy,
+    RangeInclusive<C>: DoubleEndedIterator<Item = C>,
+{
+    /// The cells within the grid's bounds, one line per row, the lowest `y` at the top
+    pub fn render(&self, render_cell: impl FnMut(Option<&T>) -> char) -> String
+    where
+        C: Default,
+    {
+        self.render_with(&RenderOptions::default(), render_cell)
+    }
+
+    /// Empty if neither the grid nor the options have any bounds
+    pub fn render_with(
+        &self,
+        options: &RenderOptions<C>,
+        render_cell: impl FnMut(Option<&T>) -> char,
+    ) -> String {
+        Canvas::from_grid(self, options, render_cell).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_are_tracked() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        grid.insert(Point2D(-3, 2), 'a');
+        grid.insert(Point2D(4, -1), 'b');
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds2D::new(Point2D(-3, -1), Point2D(4, 2)))
+        );
+        grid.retain(|_point, value| *value == 'b');
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds2D::new(Point2D(4, -1), Point2D(4, -1)))
+        );
+    }
+
+    #[test]
+    fn render() {
+        let grid: SparseGrid<()> = [Point2D(0, 0), Point2D(1, 1)].into_iter().collect();
+        let cell = |cell: Option<&()>| if cell.is_some() { '#' } else { '.' };
+        assert_eq!(grid.render(cell), "#.\n.#");
+        let options = RenderOptions {
+            padding: 1,
+            y_up: true,
+            ..Default::default()
+        };
+        assert_eq!(grid.render_with(&options, cell), "....\n..#.\n.#..\n....");
+        let options = RenderOptions {
+            viewport: Some(Bounds2D::new(Point2D(1, 0), Point2D(2, 1))),
+            ..Default::default()
+        };
+        assert_eq!(grid.render_with(&options, cell), "..\n#.");
+    }
+}
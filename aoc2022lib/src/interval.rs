@@ -0,0 +1,321 @@
+use std::ops::{Add, Sub};
+
+use crate::math::CheckedArith;
+
+/// Integer-like types intervals can span
+pub trait Endpoint:
+    Ord + Copy + Add<Output = Self> + Sub<Output = Self> + CheckedArith + From<u8>
+{
+}
+
+impl<T> Endpoint for T where
+    T: Ord + Copy + Add<Output = T> + Sub<Output = T> + CheckedArith + From<u8>
+{
+}
+
+/// Whether there's an integer between an interval ending at `end` and one starting at `start`.
+/// Nothing comes after the biggest `T`
+fn gap_between<T: Endpoint>(end: T, start: T) -> bool {
+    end.checked_add(1.into()).is_some_and(|after| after < start)
+}
+
+/// A range of integers, both ends included, never empty
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Endpoint> Interval<T> {
+    /// `None` if `start` is past `end`
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    pub fn point(point: T) -> Self {
+        Self {
+            start: point,
+            end: point,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// The number of integers in the interval
+    pub fn length(&self) -> T {
+        self.end - self.start + 1.into()
+    }
+
+    pub fn contains(&self, point: T) -> bool {
+        self.start <= point && point <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// `None` if the two don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Whether the two can be merged into one interval, i.e. overlap or are right next to each other
+    fn touches(&self, other: &Self) -> bool {
+        !gap_between(other.end, self.start) && !gap_between(self.end, other.start)
+    }
+}
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent [`Interval`]s
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Endpoint> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, point: T) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end < point);
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains(point))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // the intervals before `first` end too early, the ones from `last` start too late
+        let first = self
+            .intervals
+            .partition_point(|other| gap_between(other.end, interval.start));
+        let last = self
+            .intervals
+            .partition_point(|other| !gap_between(interval.end, other.start));
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, other| {
+                debug_assert!(merged.touches(other));
+                Interval {
+                    start: merged.start.min(other.start),
+                    end: merged.end.max(other.end),
+                }
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, point: T) {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end < point);
+        let Some(&interval) = self.intervals.get(idx) else {
+            return;
+        };
+        if !interval.contains(point) {
+            return;
+        }
+        let before = (interval.start < point).then(|| Interval {
+            end: point - 1.into(),
+            ..interval
+        });
+        let after = (point < interval.end).then(|| Interval {
+            start: point + 1.into(),
+            ..interval
+        });
+        self.intervals
+            .splice(idx..=idx, before.into_iter().chain(after));
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.iter().copied());
+        union
+    }
+
+    /// The number of integers in the set
+    pub fn total_length(&self) -> T {
+        self.intervals
+            .iter()
+            .map(Interval::length)
+            .fold(0.into(), |acc, len| acc + len)
+    }
+
+    /// The intervals missing between the first and the last integer of the set
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals.windows(2).filter_map(|pair| {
+            Interval::new(
+                pair[0].end.checked_add(1.into())?,
+                pair[1].start.checked_sub(1.into())?,
+            )
+        })
+    }
+
+    /// All the integers of `within` not in the set
+    pub fn complement(&self, within: Interval<T>) -> Self {
+        let mut complement = Self::new();
+        let mut start = within.start;
+        for interval in self.iter().filter(|interval| interval.overlaps(&within)) {
+            if start < interval.start {
+                complement.intervals.push(
+                    Interval::new(start, interval.start - 1.into()).expect("start is before"),
+                );
+            }
+            match interval.end.checked_add(1.into()) {
+                Some(after) if interval.end < within.end => start = after,
+                _ => return complement,
+            }
+        }
+        complement
+            .intervals
+            .extend(Interval::new(start, within.end));
+        complement
+    }
+}
+
+impl<T: Endpoint> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<T: Endpoint> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: i32, end: i32) -> Interval<i32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn intervals() {
+        assert!(interval(2, 8).contains_interval(&interval(3, 7)));
+        assert!(interval(5, 7).overlaps(&interval(7, 9)));
+        assert!(!interval(2, 4).overlaps(&interval(6, 8)));
+        assert_eq!(
+            interval(2, 6).intersection(&interval(4, 8)),
+            Some(interval(4, 6))
+        );
+        assert_eq!(interval(-2, 2).length(), 5);
+        assert_eq!(Interval::new(3, 2), None);
+    }
+
+    #[test]
+    fn insert_merges() {
+        let mut set: IntervalSet<i32> = [interval(12, 12), interval(2, 14), interval(16, 24)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            [interval(2, 14), interval(16, 24)]
+        );
+        set.insert(interval(15, 15));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [interval(2, 24)]);
+        assert_eq!(set.total_length(), 23);
+    }
+
+    #[test]
+    fn remove_point() {
+        let mut set: IntervalSet<i32> = [interval(-2, 24)].into_iter().collect();
+        set.remove(2);
+        set.remove(-2);
+        set.remove(100);
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            [interval(-1, 1), interval(3, 24)]
+        );
+        assert!(!set.contains(2));
+        assert!(set.contains(3));
+    }
+
+    #[test]
+    fn near_the_limits() {
+        let mut set: IntervalSet<i32> = [interval(i32::MAX - 1, i32::MAX), interval(i32::MIN, 0)]
+            .into_iter()
+            .collect();
+        set.insert(interval(i32::MAX, i32::MAX));
+        set.insert(interval(i32::MAX - 3, i32::MAX - 3));
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            [
+                interval(i32::MIN, 0),
+                interval(i32::MAX - 3, i32::MAX - 3),
+                interval(i32::MAX - 1, i32::MAX)
+            ]
+        );
+        set.insert(interval(i32::MAX - 2, i32::MAX - 2));
+        assert_eq!(set.gaps().collect::<Vec<_>>(), [interval(1, i32::MAX - 4)]);
+        assert_eq!(
+            set.complement(interval(i32::MIN, i32::MAX))
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            [interval(1, i32::MAX - 4)]
+        );
+        set.remove(i32::MAX);
+        assert!(set.contains(i32::MAX - 1));
+        assert!(!set.contains(i32::MAX));
+    }
+
+    #[test]
+    fn gaps_and_complement() {
+        let set: IntervalSet<i32> = [interval(1, 3), interval(6, 7), interval(10, 12)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            set.gaps().collect::<Vec<_>>(),
+            [interval(4, 5), interval(8, 9)]
+        );
+        assert_eq!(
+            set.complement(interval(0, 11))
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            [interval(0, 0), interval(4, 5), interval(8, 9)]
+        );
+        assert!(set.complement(interval(6, 7)).is_empty());
+        let other: IntervalSet<i32> = [interval(4, 9)].into_iter().collect();
+        assert_eq!(
+            set.union(&other).iter().copied().collect::<Vec<_>>(),
+            [interval(1, 12)]
+        );
+    }
+}
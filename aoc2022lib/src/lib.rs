@@ -1,3 +1,20 @@
 #[macro_use]
 pub mod parse;
+pub mod bitset;
+pub mod collections;
+pub mod error;
+pub mod flood;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod lines;
+pub mod math;
+pub mod ocr;
 pub mod points;
+pub mod render;
+pub mod search;
+pub mod simulation;
+pub mod stream;
+
+pub use error::{AocError, Result};
+pub use grid::SparseGrid;
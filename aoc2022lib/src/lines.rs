@@ -0,0 +1,217 @@
+use std::ops::{Add, Neg, Sub};
+
+use crate::points::{abs_diff, Point2D};
+
+/// All the points of a horizontal, vertical or 45° diagonal line, both ends included
+#[derive(Clone, Debug)]
+pub struct Segment<T> {
+    next: Option<Point2D<T>>,
+    end: Point2D<T>,
+    step: Point2D<T>,
+}
+
+impl<T> Segment<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Ord + Default + From<i8> + Copy,
+{
+    /// `None` if the line between the points is neither straight nor diagonal
+    pub fn new(start: Point2D<T>, end: Point2D<T>) -> Option<Self> {
+        let Point2D(dx, dy) = end - start;
+        let zero = T::default();
+        let is_straight = dx == zero || dy == zero;
+        let is_diagonal = abs_diff(dx, zero) == abs_diff(dy, zero);
+        (is_straight || is_diagonal).then(|| Self {
+            next: Some(start),
+            end,
+            step: (end - start).signum(),
+        })
+    }
+}
+
+impl<T> Iterator for Segment<T>
+where
+    T: Add<Output = T> + PartialEq + Copy,
+{
+    type Item = Point2D<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = (current != self.end).then(|| current + self.step);
+        Some(current)
+    }
+}
+
+/// All the points of a line with any slope, approximated with Bresenham's algorithm
+#[derive(Clone, Debug)]
+pub struct Bresenham<T> {
+    next: Option<Point2D<T>>,
+    end: Point2D<T>,
+    step: Point2D<T>,
+    dx: T,
+    dy: T,
+    error: T,
+}
+
+impl<T> Bresenham<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Neg<Output = T> + Ord + Default + From<i8> + Copy,
+{
+    pub fn new(start: Point2D<T>, end: Point2D<T>) -> Self {
+        let dx = abs_diff(start.0, end.0);
+        let dy = -abs_diff(start.1, end.1);
+        Self {
+            next: Some(start),
+            end,
+            step: (end - start).signum(),
+            dx,
+            dy,
+            error: dx + dy,
+        }
+    }
+}
+
+impl<T> Iterator for Bresenham<T>
+where
+    T: Add<Output = T> + Ord + Copy,
+{
+    type Item = Point2D<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        if current == self.end {
+            self.next = None;
+            return Some(current);
+        }
+
+        let mut next = current;
+        let double_error = self.error + self.error;
+        if double_error >= self.dy {
+            self.error = self.error + self.dy;
+            next.0 = next.0 + self.step.0;
+        }
+        if double_error <= self.dx {
+            self.error = self.error + self.dx;
+            next.1 = next.1 + self.step.1;
+        }
+        self.next = Some(next);
+        Some(current)
+    }
+}
+
+/// All the points of consecutive [`Segment`]s, each shared vertex only once.
+/// `None` if any of the segments is neither straight nor diagonal
+pub fn polyline<T>(vertices: &[Point2D<T>]) -> Option<impl Iterator<Item = Point2D<T>>>
+where
+    T: Add<Output = T> + Sub<Output = T> + Ord + Default + From<i8> + Copy,
+{
+    let segments = vertices
+        .windows(2)
+        .map(|pair| Segment::new(pair[0], pair[1]))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(
+        vertices
+            .first()
+            .copied()
+            .into_iter()
+            .chain(segments.into_iter().flat_map(|segment| segment.skip(1))),
+    )
+}
+
+/// All the points exactly `radius` away from `center` in manhattan distance, i.e. a diamond
+pub fn manhattan_circle<T>(center: Point2D<T>, radius: T) -> impl Iterator<Item = Point2D<T>>
+where
+    T: Add<Output = T> + Sub<Output = T> + Ord + Default + From<i8> + Copy,
+{
+    let zero = T::default();
+    let vertices = [
+        Point2D(radius, zero),
+        Point2D(zero, radius),
+        Point2D(zero - radius, zero),
+        Point2D(zero, zero - radius),
+    ]
+    .map(|offset| center + offset);
+
+    (0..4)
+        .flat_map(move |side| {
+            let (start, end) = (vertices[side], vertices[(side + 1) % 4]);
+            Segment::new(start, end)
+                .expect("the sides of a diamond are diagonal")
+                .take_while(move |point| *point != end)
+        })
+        .chain((radius == zero).then_some(center))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn straight_and_diagonal_segments() {
+        let points: Vec<_> = Segment::new(Point2D(498, 6), Point2D(496, 6))
+            .unwrap()
+            .collect();
+        assert_eq!(points, [Point2D(498, 6), Point2D(497, 6), Point2D(496, 6)]);
+        let points: Vec<_> = Segment::new(Point2D(0, 0), Point2D(2, -2))
+            .unwrap()
+            .collect();
+        assert_eq!(points, [Point2D(0, 0), Point2D(1, -1), Point2D(2, -2)]);
+        assert_eq!(
+            Segment::new(Point2D(1, 1), Point2D(1, 1)).unwrap().count(),
+            1
+        );
+        assert!(Segment::new(Point2D(0, 0), Point2D(2, 1)).is_none());
+    }
+
+    #[test]
+    fn bresenham() {
+        let points: Vec<_> = Bresenham::new(Point2D(0, 0), Point2D(5, 2)).collect();
+        assert_eq!(
+            points,
+            [
+                Point2D(0, 0),
+                Point2D(1, 0),
+                Point2D(2, 1),
+                Point2D(3, 1),
+                Point2D(4, 2),
+                Point2D(5, 2)
+            ]
+        );
+        let straight: Vec<_> = Bresenham::new(Point2D(3, 5), Point2D(3, 1)).collect();
+        let segment: Vec<_> = Segment::new(Point2D(3, 5), Point2D(3, 1))
+            .unwrap()
+            .collect();
+        assert_eq!(straight, segment);
+    }
+
+    #[test]
+    fn polyline() {
+        let vertices = [Point2D(498, 4), Point2D(498, 6), Point2D(496, 6)];
+        let points: Vec<_> = super::polyline(&vertices).unwrap().collect();
+        assert_eq!(
+            points,
+            [
+                Point2D(498, 4),
+                Point2D(498, 5),
+                Point2D(498, 6),
+                Point2D(497, 6),
+                Point2D(496, 6)
+            ]
+        );
+        assert!(super::polyline(&[Point2D(0, 0), Point2D(1, 5)]).is_none());
+    }
+
+    #[test]
+    fn manhattan_circle() {
+        let center = Point2D(8, 7);
+        let points: HashSet<_> = super::manhattan_circle(center, 3).collect();
+        assert_eq!(points.len(), 12);
+        assert!(points.iter().all(|point| point.manhattan(center) == 3));
+        assert_eq!(
+            super::manhattan_circle(center, 0).collect::<Vec<_>>(),
+            [center]
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+};
+
+use crate::AocError;
+
+/// Greatest common divisor of two non-negative numbers, with `gcd(0, 0) == 0`
+pub fn gcd<T>(mut a: T, mut b: T) -> T
+where
+    T: Rem<Output = T> + PartialEq + Default + Copy,
+{
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple of two non-negative numbers, with `lcm(0, n) == 0`.
+/// `None` if it overflows
+pub fn lcm<T>(a: T, b: T) -> Option<T>
+where
+    T: Rem<Output = T> + Div<Output = T> + PartialEq + Default + CheckedArith,
+{
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// `None` if there are no numbers
+pub fn gcd_all<T>(numbers: impl IntoIterator<Item = T>) -> Option<T>
+where
+    T: Rem<Output = T> + PartialEq + Default + Copy,
+{
+    numbers.into_iter().reduce(gcd)
+}
+
+/// `None` if there are no numbers, or the lcm overflows
+pub fn lcm_all<T>(numbers: impl IntoIterator<Item = T>) -> Option<T>
+where
+    T: Rem<Output = T> + Div<Output = T> + PartialEq + Default + CheckedArith,
+{
+    let mut numbers = numbers.into_iter();
+    let first = numbers.next()?;
+    numbers.try_fold(first, lcm)
+}
+
+/// `(g, x, y)` such that `a * x + b * y == g`, where `g` is the gcd of `a` and `b`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `modulus`, if they're coprime
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solves `x ≡ residue (mod modulus)` for all the given `(residue, modulus)` pairs at once,
+/// as `(x, lcm of the moduli)`. The moduli don't have to be coprime.
+/// `None` if the congruences contradict each other, there are none, a modulus isn't positive,
+/// or the lcm overflows
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| (modulus > 0).then(|| (residue.rem_euclid(modulus), modulus)))
+        .reduce(|acc, congruence| {
+            let ((r1, m1), (r2, m2)) = (acc?, congruence?);
+            let (g, x, _) = extended_gcd(m1, m2);
+            if (r2 - r1) % g != 0 {
+                return None;
+            }
+            let modulus = i128::from(m1 / g) * i128::from(m2);
+            // x is the inverse of m1 / g modulo m2 / g
+            let step = i128::from((r2 - r1) / g) * i128::from(x) % i128::from(m2 / g);
+            let residue = (i128::from(r1) + i128::from(m1) * step).rem_euclid(modulus);
+            Some((i64::try_from(residue).ok()?, i64::try_from(modulus).ok()?))
+        })?
+}
+
+fn add_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    let sum = u128::from(a) + u128::from(b);
+    u64::try_from(sum % u128::from(modulus)).expect("less than the modulus")
+}
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    let product = u128::from(a) * u128::from(b);
+    u64::try_from(product % u128::from(modulus)).expect("less than the modulus")
+}
+
+fn neg_mod(a: u64, modulus: u64) -> u64 {
+    (modulus - a) % modulus
+}
+
+fn from_signed_mod(value: i64, modulus: u64) -> u64 {
+    let value = i128::from(value).rem_euclid(i128::from(modulus));
+    u64::try_from(value).expect("less than the modulus")
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+fn inverse_mod(a: u64, modulus: u64) -> Option<u64> {
+    let a = i64::try_from(a).ok()?;
+    let modulus_i64 = i64::try_from(modulus).ok()?;
+    mod_inverse(a, modulus_i64).map(|inverse| from_signed_mod(inverse, modulus))
+}
+
+/// Overflow-checked arithmetic, e.g. for sums of input numbers that may not fit,
+/// or unsigned coordinates stepping "below zero"
+pub trait CheckedArith: Sized + Copy {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_arith {
+    ($($t:ty),+) => {
+        $(
+            impl CheckedArith for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_checked_arith!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Sums and products of numbers from the input, which may not fit
+pub trait CheckedFold: Iterator<Item: CheckedArith> + Sized {
+    fn checked_sum(mut self) -> Result<Self::Item, AocError> {
+        self.try_fold(Self::Item::ZERO, CheckedArith::checked_add)
+            .ok_or(AocError::Overflow)
+    }
+
+    fn checked_product(mut self) -> Result<Self::Item, AocError> {
+        self.try_fold(Self::Item::ONE, CheckedArith::checked_mul)
+            .ok_or(AocError::Overflow)
+    }
+}
+
+impl<I: Iterator<Item: CheckedArith>> CheckedFold for I {}
+
+/// An integer modulo `N`, which is fixed at compile time. `Mod<0>` doesn't compile
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
+pub struct Mod<const N: u64>(u64);
+
+impl<const N: u64> Mod<N> {
+    /// `N`, checked to be positive when the code using it is compiled
+    const MODULUS: u64 = {
+        assert!(N > 0, "the modulus must be positive");
+        N
+    };
+
+    pub fn new(value: u64) -> Self {
+        Self(value % Self::MODULUS)
+    }
+
+    pub fn from_signed(value: i64) -> Self {
+        Self(from_signed_mod(value, Self::MODULUS))
+    }
+
+    /// Always in `0..N`
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    #[must_use]
+    pub fn pow(self, exponent: u64) -> Self {
+        Self(pow_mod(self.0, exponent, Self::MODULUS))
+    }
+
+    /// `None` if the value and `N` aren't coprime
+    pub fn inverse(self) -> Option<Self> {
+        inverse_mod(self.0, Self::MODULUS).map(Self)
+    }
+}
+
+impl<const N: u64> Add for Mod<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(add_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+
+impl<const N: u64> Sub for Mod<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const N: u64> Mul for Mod<N> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(mul_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+
+impl<const N: u64> Neg for Mod<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(neg_mod(self.0, Self::MODULUS))
+    }
+}
+
+impl<const N: u64> AddAssign for Mod<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const N: u64> SubAssign for Mod<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: u64> MulAssign for Mod<N> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const N: u64> Display for Mod<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An integer modulo a number only known at runtime.
+/// Both sides of an operation must have the same modulus
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct ModInt {
+    value: u64,
+    modulus: u64,
+}
+
+impl ModInt {
+    /// Panics if `modulus` is 0, callers working from puzzle input should check it first
+    pub fn new(value: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "the modulus must be positive");
+        Self {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    /// Panics if `modulus` is 0, like [`ModInt::new`]
+    pub fn from_signed(value: i64, modulus: u64) -> Self {
+        assert!(modulus > 0, "the modulus must be positive");
+        Self {
+            value: from_signed_mod(value, modulus),
+            modulus,
+        }
+    }
+
+    /// Always in `0..modulus`
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+
+    #[must_use]
+    pub fn pow(self, exponent: u64) -> Self {
+        Self {
+            value: pow_mod(self.value, exponent, self.modulus),
+            ..self
+        }
+    }
+
+    /// `None` if the value and the modulus aren't coprime
+    pub fn inverse(self) -> Option<Self> {
+        let value = inverse_mod(self.value, self.modulus)?;
+        Some(Self { value, ..self })
+    }
+
+    fn same_modulus(self, rhs: Self) -> u64 {
+        assert_eq!(self.modulus, rhs.modulus, "different moduli");
+        self.modulus
+    }
+}
+
+impl Add for ModInt {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let modulus = self.same_modulus(rhs);
+        Self {
+            value: add_mod(self.value, rhs.value, modulus),
+            modulus,
+        }
+    }
+}
+
+impl Sub for ModInt {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for ModInt {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let modulus = self.same_modulus(rhs);
+        Self {
+            value: mul_mod(self.value, rhs.value, modulus),
+            modulus,
+        }
+    }
+}
+
+impl Neg for ModInt {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            value: neg_mod(self.value, self.modulus),
+            ..self
+        }
+    }
+}
+
+impl AddAssign for ModInt {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for ModInt {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for ModInt {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Display for ModInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(0u64, 6), Some(0));
+        assert_eq!(lcm_all([23u64, 19, 13, 17]), Some(96_577));
+        assert_eq!(lcm_all([1u8 << 7, 3]), None);
+        assert_eq!(lcm_all(Vec::<u8>::new()), None);
+        assert_eq!(gcd_all(Vec::<u8>::new()), None);
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // moduli sharing a factor
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(1, 0)]), None);
+        assert_eq!(crt([(2, 3), (1, 0)]), None);
+        assert_eq!(crt([(2, 3), (1, -5)]), None);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        let a = Mod::<7>::new(5);
+        let b = Mod::<7>::from_signed(-1);
+        assert_eq!(b.value(), 6);
+        assert_eq!((a + b).value(), 4);
+        assert_eq!((a - b).value(), 6);
+        assert_eq!((a * b).value(), 2);
+        assert_eq!(a.pow(6).value(), 1);
+        assert_eq!((a * a.inverse().unwrap()).value(), 1);
+
+        let c = ModInt::from_signed(-7, 5);
+        assert_eq!(c.value(), 3);
+        assert_eq!((c * ModInt::new(4, 5)).value(), 2);
+        assert_eq!(ModInt::new(2, 4).inverse(), None);
+    }
+
+    #[test]
+    #[should_panic = "the modulus must be positive"]
+    fn mod_int_modulo_0() {
+        let _ = ModInt::from_signed(-1, 0);
+    }
+
+    #[test]
+    fn checked_folds() {
+        assert_eq!([1u8, 2, 3].into_iter().checked_sum(), Ok(6));
+        assert_eq!(
+            [200u8, 100].into_iter().checked_sum(),
+            Err(AocError::Overflow)
+        );
+        assert_eq!(Vec::<i32>::new().into_iter().checked_product(), Ok(1));
+        assert_eq!(
+            [16u8, 16].into_iter().checked_product(),
+            Err(AocError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Display;
+
+/// The letters Advent of Code draws with lit pixels
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Font {
+    /// 4 pixels wide and 6 tall, 1 empty column between letters
+    Small,
+    /// 6 pixels wide and 10 tall, 2 empty columns between letters
+    Large,
+}
+
+impl Font {
+    fn width(self) -> usize {
+        match self {
+            Font::Small => 4,
+            Font::Large => 6,
+        }
+    }
+
+    fn height(self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    fn spacing(self) -> usize {
+        match self {
+            Font::Small => 1,
+            Font::Large => 2,
+        }
+    }
+
+    fn glyphs(self) -> &'static [(char, &'static str)] {
+        match self {
+            Font::Small => SMALL_GLYPHS,
+            Font::Large => LARGE_GLYPHS,
+        }
+    }
+
+    /// The font that's exactly this many pixels tall
+    pub fn with_height(height: usize) -> Option<Self> {
+        [Font::Small, Font::Large]
+            .into_iter()
+            .find(|font| font.height() == height)
+    }
+}
+
+const SMALL_GLYPHS: &[(char, &str)] = &[
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Y', "#...#....#.#..#...#...#."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+const LARGE_GLYPHS: &[(char, &str)] = &[
+    (
+        'A',
+        "..##...#..#.#....##....##....########....##....##....##....#",
+    ),
+    (
+        'B',
+        "#####.#....##....##....######.#....##....##....##....######.",
+    ),
+    (
+        'C',
+        ".####.#....##.....#.....#.....#.....#.....#.....#....#.####.",
+    ),
+    (
+        'E',
+        "#######.....#.....#.....#####.#.....#.....#.....#.....######",
+    ),
+    (
+        'F',
+        "#######.....#.....#.....#####.#.....#.....#.....#.....#.....",
+    ),
+    (
+        'G',
+        ".####.#....##.....#.....#.....#..####....##....##...##.###.#",
+    ),
+    (
+        'H',
+        "#....##....##....##....########....##....##....##....##....#",
+    ),
+    (
+        'J',
+        "...###....#.....#.....#.....#.....#.....#.#...#.#...#..###..",
+    ),
+    (
+        'K',
+        "#....##...#.#..#..#.#...##....##....#.#...#..#..#...#.#....#",
+    ),
+    (
+        'L',
+        "#.....#.....#.....#.....#.....#.....#.....#.....#.....######",
+    ),
+    (
+        'N',
+        "#....###...###...##.#..##.#..##..#.##..#.##...###...###....#",
+    ),
+    (
+        'P',
+        "#####.#....##....##....######.#.....#.....#.....#.....#.....",
+    ),
+    (
+        'R',
+        "#####.#....##....##....######.#..#..#...#.#...#.#....##....#",
+    ),
+    (
+        'X',
+        "#....##....#.#..#..#..#...##....##...#..#..#..#.#....##....#",
+    ),
+    (
+        'Z',
+        "######.....#.....#....#....#....#....#....#.....#.....######",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// No font is this many pixels tall
+    UnsupportedHeight(usize),
+    /// The rows of the picture aren't all equally wide
+    RaggedRows,
+    /// Which letters, counting from 0, didn't look like any in the font
+    UnknownGlyphs(Vec<usize>),
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(height) => {
+                write!(f, "no font is {height} pixels tall")
+            }
+            OcrError::RaggedRows => write!(f, "the rows aren't all equally wide"),
+            OcrError::UnknownGlyphs(positions) => {
+                write!(f, "unknown letters at positions {positions:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Reads letters from rows of pixels, the font is told apart by the number of rows
+pub fn decode_pixels<R: AsRef<[bool]>>(rows: &[R]) -> Result<String, OcrError> {
+    let font = Font::with_height(rows.len()).ok_or(OcrError::UnsupportedHeight(rows.len()))?;
+    let width = rows.first().map_or(0, |row| row.as_ref().len());
+    if rows.iter().any(|row| row.as_ref().len() != width) {
+        return Err(OcrError::RaggedRows);
+    }
+
+    let pitch = font.width() + font.spacing();
+    let mut letters = String::new();
+    let mut unknown = Vec::new();
+    // the spacing after the last letter may have been cut off
+    for (position, left) in (0..width + font.spacing()).step_by(pitch).enumerate() {
+        if left + font.width() > width {
+            break;
+        }
+        let glyph: String = rows
+            .iter()
+            .flat_map(|row| &row.as_ref()[left..left + font.width()])
+            .map(|&lit| if lit { '#' } else { '.' })
+            .collect();
+        match font.glyphs().iter().find(|(_, pixels)| *pixels == glyph) {
+            Some(&(letter, _)) => letters.push(letter),
+            None => unknown.push(position),
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(letters)
+    } else {
+        Err(OcrError::UnknownGlyphs(unknown))
+    }
+}
+
+/// Like [`decode_pixels`], with `#` for lit pixels and anything else for dark ones
+pub fn decode(picture: &str) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = picture
+        .lines()
+        .map(|line| line.chars().map(|pixel| pixel == '#').collect())
+        .collect();
+    decode_pixels(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_font() {
+        let picture = "\
+###..#..#.#....#..#...##..##..####..##..
+#..#.#..#.#....#..#....#.#..#....#.#..#.
+#..#.####.#....####....#.#......#..#..#.
+###..#..#.#....#..#....#.#.##..#...####.
+#....#..#.#....#..#.#..#.#..#.#....#..#.
+#....#..#.####.#..#..##...###.####.#..#.";
+        assert_eq!(decode(picture).unwrap(), "PHLHJGZA");
+    }
+
+    #[test]
+    fn large_font() {
+        let picture = "\
+#....#..######
+#....#..######
+#....#..######
+#....#..######
+######..######
+#....#..######
+#....#..######
+#....#..######
+#....#..######
+#....#..######";
+        assert_eq!(decode(picture), Err(OcrError::UnknownGlyphs(vec![1])));
+        let h = picture.lines().map(|line| &line[..6]).collect::<Vec<_>>();
+        assert_eq!(decode(&h.join("\n")).unwrap(), "H");
+    }
+
+    #[test]
+    fn unsupported_pictures() {
+        assert_eq!(decode("#\n#"), Err(OcrError::UnsupportedHeight(2)));
+        assert_eq!(decode("#\n#\n#\n#\n#\n##"), Err(OcrError::RaggedRows));
+    }
+}
@@ -1,48 +1,257 @@
+use std::{collections::HashMap, fmt::Display, hash::BuildHasher};
+
 use nom;
 
 pub fn n<N: std::str::FromStr>(input: &str) -> nom::IResult<&str, N> {
     nom::combinator::map_res(nom::character::complete::digit1, N::from_str)(input)
 }
 
+/// Where in the input something is, both counted from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What was left to parse when parsing failed
+    pub input: String,
+    pub reason: String,
+    pub location: Option<Location>,
+}
+
+impl ParseError {
+    pub fn new(input: &str, reason: impl Display) -> Self {
+        Self {
+            input: input.to_string(),
+            reason: reason.to_string(),
+            location: None,
+        }
+    }
+
+    /// Failed with `remaining`, the end of `whole`, left to parse
+    pub fn at(whole: &str, remaining: &str, reason: impl Display) -> Self {
+        let parsed = &whole[..whole.len() - remaining.len()];
+        let (line, column) = match parsed.rsplit_once('\n') {
+            Some((before, current_line)) => (
+                before.matches('\n').count() + 2,
+                current_line.chars().count() + 1,
+            ),
+            None => (1, parsed.chars().count() + 1),
+        };
+        Self {
+            location: Some(Location { line, column }),
+            ..Self::new(remaining, reason)
+        }
+    }
+
+    /// The same error, for input that was cut out starting at line `line` of something bigger
+    #[must_use]
+    pub fn on_line(self, line: usize) -> Self {
+        let location = match self.location {
+            Some(location) => Location {
+                line: location.line + line - 1,
+                ..location
+            },
+            None => Location { line, column: 1 },
+        };
+        Self {
+            location: Some(location),
+            ..self
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{location}: ")?;
+        }
+        if self.input.is_empty() {
+            write!(f, "failed to parse: {}", self.reason)
+        } else {
+            write!(f, "failed to parse {:?}: {}", self.input, self.reason)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<nom::error::Error<String>> for ParseError {
+    fn from(nom::error::Error { input, code }: nom::error::Error<String>) -> Self {
+        Self::new(&input, code.description())
+    }
+}
+
+/// The object a nom parser made of all of `s`, anything but whitespace left over is an error.
+/// What the `impl_*_from_nom_parser!` macros parse with
+pub fn finish<'a, T>(s: &'a str, parsed: nom::IResult<&'a str, T>) -> Result<T, ParseError> {
+    use nom::Finish;
+    match parsed.finish() {
+        Ok((remaining, object)) if remaining.trim().is_empty() => Ok(object),
+        Ok((remaining, _)) => Err(ParseError::at(
+            s,
+            remaining.trim_start(),
+            "unexpected input",
+        )),
+        Err(nom::error::Error { input, code }) => Err(ParseError::at(s, input, code.description())),
+    }
+}
+
+/// Like [`std::str::FromStr`], but the parsed object may borrow from the input
+pub trait Parse<'a>: Sized {
+    fn parse(s: &'a str) -> Result<Self, ParseError>;
+}
+
+impl<'a> Parse<'a> for &'a str {
+    fn parse(s: &'a str) -> Result<Self, ParseError> {
+        Ok(s)
+    }
+}
+
+/// One element per line
+impl<'a, T: Parse<'a>> Parse<'a> for Vec<T> {
+    fn parse(s: &'a str) -> Result<Self, ParseError> {
+        s.lines()
+            .enumerate()
+            .map(|(idx, line)| T::parse(line).map_err(|err| err.on_line(idx + 1)))
+            .collect()
+    }
+}
+
+/// One `key: value` entry per line, each key only once
+impl<'a, K, V, S> Parse<'a> for HashMap<K, V, S>
+where
+    K: Parse<'a> + Eq + std::hash::Hash,
+    V: Parse<'a>,
+    S: BuildHasher + Default,
+{
+    fn parse(s: &'a str) -> Result<Self, ParseError> {
+        let mut map = HashMap::default();
+        for (idx, line) in s.lines().enumerate() {
+            let (raw_key, value) = line
+                .split_once(": ")
+                .ok_or_else(|| ParseError::new(line, "no `: ` separator"))
+                .map_err(|err| err.on_line(idx + 1))?;
+            let value_start = &line[raw_key.len() + 2..];
+            let key = K::parse(raw_key).map_err(|err| err.on_line(idx + 1))?;
+            let value = V::parse(value)
+                .map_err(|err| ParseError::at(line, value_start, err.reason).on_line(idx + 1))?;
+            if map.insert(key, value).is_some() {
+                return Err(ParseError::new(raw_key, "duplicate key").on_line(idx + 1));
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// Implements [`Parse`] for owned types through their [`std::str::FromStr`] impl
+#[macro_export]
+macro_rules! impl_parse_via_from_str {
+    ($($obj:ty),+ $(,)?) => {
+        $(
+            impl<'a> $crate::parse::Parse<'a> for $obj {
+                fn parse(s: &'a str) -> Result<Self, $crate::parse::ParseError> {
+                    <$obj as std::str::FromStr>::from_str(s)
+                        .map_err(|err| $crate::parse::ParseError::new(s, err))
+                }
+            }
+        )+
+    };
+}
+
+impl_parse_via_from_str!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char, String,
+);
+
 #[macro_export]
 macro_rules! impl_from_str_from_nom_parser {
     ($fn:ident, $obj:ident) => {
         impl std::str::FromStr for $obj {
-            type Err = nom::error::Error<String>;
+            type Err = $crate::parse::ParseError;
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                use nom::Finish;
-                match $fn(s).finish() {
-                    Ok((_remaining, object)) => Ok(object),
-                    Err(nom::error::Error { input, code }) => Err(Self::Err {
-                        input: input.to_string(),
-                        code,
-                    }),
-                }
+                $crate::parse::finish(s, $fn(s))
+            }
+        }
+
+        impl<'a> $crate::parse::Parse<'a> for $obj {
+            fn parse(s: &'a str) -> Result<Self, $crate::parse::ParseError> {
+                <$obj as std::str::FromStr>::from_str(s)
             }
         }
     };
 }
 
+/// Implements [`Parse`] from a nom parser, also for objects borrowing from the input:
+/// `impl_parse_from_nom_parser!(monkey, Monkey<'a>)`
 #[macro_export]
-macro_rules! impl_from_str_for_obj_with_lieftimes_from_nom_parser {
+macro_rules! impl_parse_from_nom_parser {
+    ($fn:ident, $obj:ident<$lt:lifetime>) => {
+        $crate::impl_parse_from_nom_parser!(@impl $fn, $lt, $obj<$lt>);
+    };
     ($fn:ident, $obj:ident) => {
-        impl<'input, 'output> TryFrom<&'input str> for $obj<'output>
-        where
-            'input: 'output,
-        {
-            type Error = nom::error::Error<String>;
-
-            fn try_from(value: &'input str) -> Result<Self, Self::Error> {
-                use nom::Finish;
-                match $fn(value).finish() {
-                    Ok((_remaining, object)) => Ok(object),
-                    Err(nom::error::Error { input, code }) => Err(Self::Error {
-                        input: input.to_string(),
-                        code,
-                    }),
-                }
+        $crate::impl_parse_from_nom_parser!(@impl $fn, 'a, $obj);
+    };
+    (@impl $fn:ident, $lt:lifetime, $ty:ty) => {
+        impl<$lt> $crate::parse::Parse<$lt> for $ty {
+            fn parse(s: &$lt str) -> Result<Self, $crate::parse::ParseError> {
+                $crate::parse::finish(s, $fn(s))
             }
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_borrowed_lines() {
+        let input = String::from("abc\ndef");
+        let lines = Vec::<&str>::parse(&input).unwrap();
+        assert_eq!(lines, ["abc", "def"]);
+    }
+
+    #[test]
+    fn parse_map() {
+        let map = HashMap::<&str, u32>::parse("root: 5\nhumn: 7").unwrap();
+        assert_eq!(map["root"], 5);
+        assert_eq!(map["humn"], 7);
+    }
+
+    #[test]
+    fn parse_map_with_duplicate_key() {
+        let err = HashMap::<&str, u32>::parse("root: 5\nroot: 7").unwrap_err();
+        assert_eq!(err.reason, "duplicate key");
+        assert_eq!(err.location, Some(Location { line: 2, column: 1 }));
+    }
+
+    #[test]
+    fn leftover_input() {
+        let err = finish("12x", n::<u32>("12x")).unwrap_err();
+        assert_eq!(err.input, "x");
+        assert_eq!(err.location, Some(Location { line: 1, column: 3 }));
+        assert_eq!(finish("12\n", n::<u32>("12\n")), Ok(12));
+    }
+
+    #[test]
+    fn parse_map_without_separator() {
+        assert!(HashMap::<&str, u32>::parse("root 5").is_err());
+    }
+
+    #[test]
+    fn error_locations() {
+        let err = HashMap::<&str, u32>::parse("root: 5\nhumn: x").unwrap_err();
+        assert_eq!(err.location, Some(Location { line: 2, column: 7 }));
+        let err = Vec::<u32>::parse("1\n2\n-3").unwrap_err();
+        assert_eq!(err.location, Some(Location { line: 3, column: 1 }));
+    }
+}
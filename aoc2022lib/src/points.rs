@@ -1,6 +1,31 @@
-use std::ops::{Add, Sub};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+use itertools::iproduct;
+
+use crate::math::CheckedArith;
+
+mod bounds;
+mod rotation;
+pub use bounds::{Bounds2D, Bounds3D};
+pub use rotation::{canonical_orientation, Rotation};
+
+pub(crate) fn abs_diff<T: Sub<Output = T> + Ord>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn signum<T: Ord + Default + From<i8>>(a: T) -> T {
+    match a.cmp(&T::default()) {
+        std::cmp::Ordering::Less => (-1).into(),
+        std::cmp::Ordering::Equal => 0.into(),
+        std::cmp::Ordering::Greater => 1.into(),
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
 pub struct Point2D<T, U = T>(pub T, pub U);
 
 impl<T: Copy, U: Copy> Point2D<T, U> {
@@ -17,6 +42,105 @@ impl<T: Copy, U: Copy> Point2D<T, U> {
     }
 }
 
+impl<T, U> Point2D<T, U>
+where
+    T: Add<Output = T> + Sub<Output = T> + From<i8> + Copy,
+    U: Add<Output = U> + Sub<Output = U> + From<i8> + Copy,
+{
+    /// Up, down, left and right
+    pub fn neighbours(&self) -> [Self; 4] {
+        let &Self(x, y) = self;
+        [
+            (x + 1.into(), y),
+            (x, y + 1.into()),
+            (x - 1.into(), y),
+            (x, y - 1.into()),
+        ]
+        .map(|(x, y)| Self(x, y))
+    }
+
+    /// Like [`Point2D::neighbours`], plus the four diagonal ones
+    pub fn neighbours_with_diagonals(&self) -> [Self; 8] {
+        let &Self(x, y) = self;
+        [
+            (x + 1.into(), y),
+            (x + 1.into(), y + 1.into()),
+            (x, y + 1.into()),
+            (x - 1.into(), y + 1.into()),
+            (x - 1.into(), y),
+            (x - 1.into(), y - 1.into()),
+            (x, y - 1.into()),
+            (x + 1.into(), y - 1.into()),
+        ]
+        .map(|(x, y)| Self(x, y))
+    }
+}
+
+impl<T: CheckedArith, U: CheckedArith> Point2D<T, U> {
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self(self.0.checked_add(rhs.0)?, self.1.checked_add(rhs.1)?))
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(Self(self.0.checked_sub(rhs.0)?, self.1.checked_sub(rhs.1)?))
+    }
+}
+
+impl<T, U> Point2D<T, U>
+where
+    T: CheckedArith + From<u8>,
+    U: CheckedArith + From<u8>,
+{
+    /// [`Point2D::neighbours`] which don't overflow, e.g. no negative ones for unsigned coordinates
+    pub fn checked_neighbours(self) -> impl Iterator<Item = Self> {
+        let Self(x, y) = self;
+        [
+            x.checked_add(1.into()).map(|x| Self(x, y)),
+            y.checked_add(1.into()).map(|y| Self(x, y)),
+            x.checked_sub(1.into()).map(|x| Self(x, y)),
+            y.checked_sub(1.into()).map(|y| Self(x, y)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl<T> Point2D<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Ord + Copy,
+{
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.0, other.0) + abs_diff(self.1, other.1)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.0, other.0).max(abs_diff(self.1, other.1))
+    }
+}
+
+impl<T, U> Point2D<T, U>
+where
+    T: Ord + Default + From<i8>,
+    U: Ord + Default + From<i8>,
+{
+    /// The sign of each coordinate, i.e. a single step towards `self` from the origin
+    pub fn signum(self) -> Self {
+        Self(signum(self.0), signum(self.1))
+    }
+}
+
+impl<T, U> From<(T, U)> for Point2D<T, U> {
+    fn from((x, y): (T, U)) -> Self {
+        Self(x, y)
+    }
+}
+
+impl<T, U> From<Point2D<T, U>> for (T, U) {
+    fn from(Point2D(x, y): Point2D<T, U>) -> Self {
+        (x, y)
+    }
+}
+
 impl<T, U> Add for Point2D<T, U>
 where
     T: Add<Output = T>,
@@ -29,7 +153,71 @@ where
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+impl<T, U> Sub for Point2D<T, U>
+where
+    T: Sub<Output = T>,
+    U: Sub<Output = U>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl<T, U> Neg for Point2D<T, U>
+where
+    T: Neg<Output = T>,
+    U: Neg<Output = U>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0, -self.1)
+    }
+}
+
+/// Scaling by a scalar
+impl<T, U, S> Mul<S> for Point2D<T, U>
+where
+    T: Mul<S, Output = T>,
+    U: Mul<S, Output = U>,
+    S: Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: S) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl<T: AddAssign, U: AddAssign> AddAssign for Point2D<T, U> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+    }
+}
+
+impl<T: SubAssign, U: SubAssign> SubAssign for Point2D<T, U> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+        self.1 -= rhs.1;
+    }
+}
+
+impl<T, U, S> MulAssign<S> for Point2D<T, U>
+where
+    T: MulAssign<S>,
+    U: MulAssign<S>,
+    S: Copy,
+{
+    fn mul_assign(&mut self, rhs: S) {
+        self.0 *= rhs;
+        self.1 *= rhs;
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
 pub struct Point3D<T, U = T, V = T>(pub T, pub U, pub V);
 
 impl<T, U, V> Point3D<T, U, V>
@@ -50,6 +238,58 @@ where
         ]
         .map(|(x, y, z)| Self(x, y, z))
     }
+
+    /// Like [`Point3D::neighbours`], plus the ones sharing only an edge or a corner
+    pub fn neighbours_with_diagonals(&self) -> [Self; 26] {
+        let &Self(x, y, z) = self;
+        let offsets =
+            iproduct!(-1..=1, -1..=1, -1..=1).filter(|&offset: &(i8, i8, i8)| offset != (0, 0, 0));
+        let mut neighbours = [*self; 26];
+        for (neighbour, (dx, dy, dz)) in neighbours.iter_mut().zip(offsets) {
+            *neighbour = Self(x + dx.into(), y + dy.into(), z + dz.into());
+        }
+        neighbours
+    }
+}
+
+impl<T: CheckedArith, U: CheckedArith, V: CheckedArith> Point3D<T, U, V> {
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self(
+            self.0.checked_add(rhs.0)?,
+            self.1.checked_add(rhs.1)?,
+            self.2.checked_add(rhs.2)?,
+        ))
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(Self(
+            self.0.checked_sub(rhs.0)?,
+            self.1.checked_sub(rhs.1)?,
+            self.2.checked_sub(rhs.2)?,
+        ))
+    }
+}
+
+impl<T, U, V> Point3D<T, U, V>
+where
+    T: CheckedArith + From<u8>,
+    U: CheckedArith + From<u8>,
+    V: CheckedArith + From<u8>,
+{
+    /// [`Point3D::neighbours`] which don't overflow, e.g. no negative ones for unsigned coordinates
+    pub fn checked_neighbours(self) -> impl Iterator<Item = Self> {
+        let Self(x, y, z) = self;
+        [
+            x.checked_add(1.into()).map(|x| Self(x, y, z)),
+            y.checked_add(1.into()).map(|y| Self(x, y, z)),
+            z.checked_add(1.into()).map(|z| Self(x, y, z)),
+            x.checked_sub(1.into()).map(|x| Self(x, y, z)),
+            y.checked_sub(1.into()).map(|y| Self(x, y, z)),
+            z.checked_sub(1.into()).map(|z| Self(x, y, z)),
+        ]
+        .into_iter()
+        .flatten()
+    }
 }
 
 impl<T: Copy, U: Copy, V: Copy> Point3D<T, U, V> {
@@ -70,6 +310,45 @@ impl<T: Copy, U: Copy, V: Copy> Point3D<T, U, V> {
     }
 }
 
+impl<T> Point3D<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Ord + Copy,
+{
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.0, other.0) + abs_diff(self.1, other.1) + abs_diff(self.2, other.2)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.0, other.0)
+            .max(abs_diff(self.1, other.1))
+            .max(abs_diff(self.2, other.2))
+    }
+}
+
+impl<T, U, V> Point3D<T, U, V>
+where
+    T: Ord + Default + From<i8>,
+    U: Ord + Default + From<i8>,
+    V: Ord + Default + From<i8>,
+{
+    /// The sign of each coordinate, i.e. a single step towards `self` from the origin
+    pub fn signum(self) -> Self {
+        Self(signum(self.0), signum(self.1), signum(self.2))
+    }
+}
+
+impl<T, U, V> From<(T, U, V)> for Point3D<T, U, V> {
+    fn from((x, y, z): (T, U, V)) -> Self {
+        Self(x, y, z)
+    }
+}
+
+impl<T, U, V> From<Point3D<T, U, V>> for (T, U, V) {
+    fn from(Point3D(x, y, z): Point3D<T, U, V>) -> Self {
+        (x, y, z)
+    }
+}
+
 impl<T, U, V> Add for Point3D<T, U, V>
 where
     T: Add<Output = T>,
@@ -82,3 +361,325 @@ where
         Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
     }
 }
+
+impl<T, U, V> Sub for Point3D<T, U, V>
+where
+    T: Sub<Output = T>,
+    U: Sub<Output = U>,
+    V: Sub<Output = V>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<T, U, V> Neg for Point3D<T, U, V>
+where
+    T: Neg<Output = T>,
+    U: Neg<Output = U>,
+    V: Neg<Output = V>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0, -self.1, -self.2)
+    }
+}
+
+/// Scaling by a scalar
+impl<T, U, V, S> Mul<S> for Point3D<T, U, V>
+where
+    T: Mul<S, Output = T>,
+    U: Mul<S, Output = U>,
+    V: Mul<S, Output = V>,
+    S: Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: S) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
+impl<T: AddAssign, U: AddAssign, V: AddAssign> AddAssign for Point3D<T, U, V> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+        self.2 += rhs.2;
+    }
+}
+
+impl<T: SubAssign, U: SubAssign, V: SubAssign> SubAssign for Point3D<T, U, V> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+        self.1 -= rhs.1;
+        self.2 -= rhs.2;
+    }
+}
+
+impl<T, U, V, S> MulAssign<S> for Point3D<T, U, V>
+where
+    T: MulAssign<S>,
+    U: MulAssign<S>,
+    V: MulAssign<S>,
+    S: Copy,
+{
+    fn mul_assign(&mut self, rhs: S) {
+        self.0 *= rhs;
+        self.1 *= rhs;
+        self.2 *= rhs;
+    }
+}
+
+/// A point with any number of dimensions, indexable by axis: `point[0]` is x, `point[1]` y etc.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+impl<T: Copy, const N: usize> Point<T, N> {
+    pub fn new(coords: [T; N]) -> Self {
+        Self(coords)
+    }
+
+    pub fn coords(&self) -> [T; N] {
+        self.0
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(T, T) -> T) -> Self {
+        Self(std::array::from_fn(|axis| f(self[axis], other[axis])))
+    }
+}
+
+impl<T: Copy + Ord, const N: usize> Point<T, N> {
+    /// The smallest coordinate of the two points on each axis
+    pub fn min(self, other: Self) -> Self {
+        self.zip_with(other, Ord::min)
+    }
+
+    /// The biggest coordinate of the two points on each axis
+    pub fn max(self, other: Self) -> Self {
+        self.zip_with(other, Ord::max)
+    }
+}
+
+impl<T, const N: usize> Point<T, N>
+where
+    T: CheckedArith + From<u8>,
+{
+    /// The `2 * N` points one step away along a single axis, but for those past the edges of
+    /// what `T` can hold, like below 0 for unsigned coordinates
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        (0..N).flat_map(move |axis| {
+            [T::checked_add as fn(T, T) -> Option<T>, T::checked_sub]
+                .into_iter()
+                .filter_map(move |step| {
+                    let mut neighbour = self;
+                    neighbour[axis] = step(self[axis], 1.into())?;
+                    Some(neighbour)
+                })
+        })
+    }
+}
+
+impl<T, const N: usize> Point<T, N>
+where
+    T: Add<Output = T> + Sub<Output = T> + Ord + Default + Copy,
+{
+    pub fn manhattan(self, other: Self) -> T {
+        (0..N)
+            .map(|axis| abs_diff(self[axis], other[axis]))
+            .fold(T::default(), |acc, diff| acc + diff)
+    }
+}
+
+impl<T: Default, const N: usize> Default for Point<T, N> {
+    fn default() -> Self {
+        Self(std::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Self(coords)
+    }
+}
+
+impl<T> From<Point2D<T>> for Point<T, 2> {
+    fn from(Point2D(x, y): Point2D<T>) -> Self {
+        Self([x, y])
+    }
+}
+
+impl<T> From<Point<T, 2>> for Point2D<T> {
+    fn from(Point([x, y]): Point<T, 2>) -> Self {
+        Self(x, y)
+    }
+}
+
+impl<T> From<Point3D<T>> for Point<T, 3> {
+    fn from(Point3D(x, y, z): Point3D<T>) -> Self {
+        Self([x, y, z])
+    }
+}
+
+impl<T> From<Point<T, 3>> for Point3D<T> {
+    fn from(Point([x, y, z]): Point<T, 3>) -> Self {
+        Self(x, y, z)
+    }
+}
+
+impl<T: Add<Output = T> + Copy, const N: usize> Add for Point<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, Add::add)
+    }
+}
+
+impl<T: Sub<Output = T> + Copy, const N: usize> Sub for Point<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, Sub::sub)
+    }
+}
+
+impl<T: Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(Neg::neg))
+    }
+}
+
+/// Scaling by a scalar
+impl<T, S, const N: usize> Mul<S> for Point<T, N>
+where
+    T: Mul<S, Output = T>,
+    S: Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: S) -> Self::Output {
+        Self(self.0.map(|coord| coord * rhs))
+    }
+}
+
+impl<T: AddAssign, const N: usize> AddAssign for Point<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (coord, rhs) in self.0.iter_mut().zip(rhs.0) {
+            *coord += rhs;
+        }
+    }
+}
+
+impl<T: SubAssign, const N: usize> SubAssign for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (coord, rhs) in self.0.iter_mut().zip(rhs.0) {
+            *coord -= rhs;
+        }
+    }
+}
+
+impl<T: MulAssign<S>, S: Copy, const N: usize> MulAssign<S> for Point<T, N> {
+    fn mul_assign(&mut self, rhs: S) {
+        for coord in &mut self.0 {
+            *coord *= rhs;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let mut point = Point2D(1, -2) + Point2D(3, 4) - Point2D(1, 1);
+        assert_eq!(point, Point2D(3, 1));
+        point *= 2;
+        point -= -Point2D(1, 1);
+        assert_eq!(point, Point2D(7, 3));
+        assert_eq!(Point3D(1, 2, 3) * 3, Point3D(3, 6, 9));
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(Point2D(2, 18).manhattan(Point2D(-2, 15)), 7);
+        assert_eq!(Point2D(2, 18).chebyshev(Point2D(-2, 15)), 4);
+        assert_eq!(Point3D(1u8, 2, 3).manhattan(Point3D(3, 2, 1)), 4);
+    }
+
+    #[test]
+    fn signum() {
+        assert_eq!(Point2D(-5, 0).signum(), Point2D(-1, 0));
+        assert_eq!(Point3D(2, -1, 0).signum(), Point3D(1, -1, 0));
+    }
+
+    #[test]
+    fn checked_neighbours_of_origin() {
+        let neighbours: Vec<_> = Point2D::<usize>(0, 0).checked_neighbours().collect();
+        assert_eq!(neighbours, [Point2D(1, 0), Point2D(0, 1)]);
+        assert_eq!(Point2D::<u32>(0, 5).checked_sub(Point2D(1, 0)), None);
+    }
+
+    #[test]
+    fn tuple_conversions() {
+        assert_eq!(Point2D::from((1, 2)), Point2D(1, 2));
+        assert_eq!(<(i8, i8, i8)>::from(Point3D(1, 2, 3)), (1, 2, 3));
+    }
+
+    #[test]
+    fn n_dimensional_neighbours() {
+        let origin = Point::<i32, 4>::default();
+        let neighbours: Vec<_> = origin.neighbours().collect();
+        assert_eq!(neighbours.len(), 8);
+        assert!(neighbours.iter().all(|point| point.manhattan(origin) == 1));
+        assert!(neighbours.contains(&Point([0, 0, 0, -1])));
+    }
+
+    #[test]
+    fn unsigned_n_dimensional_neighbours() {
+        let neighbours: Vec<_> = Point::<u32, 4>([0, 5, 0, u32::MAX]).neighbours().collect();
+        assert_eq!(
+            neighbours,
+            [
+                Point([1, 5, 0, u32::MAX]),
+                Point([0, 6, 0, u32::MAX]),
+                Point([0, 4, 0, u32::MAX]),
+                Point([0, 5, 1, u32::MAX]),
+                Point([0, 5, 0, u32::MAX - 1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn n_dimensional_arithmetic() {
+        let mut point = Point([1, 2, 3]) + Point([1, 1, 1]) * 2;
+        point[2] -= 5;
+        assert_eq!(point, Point([3, 4, 0]));
+        assert_eq!(-point, Point([-3, -4, 0]));
+        assert_eq!(point.min(Point([5, 0, 5])), Point([3, 0, 0]));
+    }
+
+    #[test]
+    fn n_dimensional_conversions() {
+        assert_eq!(Point::from(Point3D(1, 2, 3)), Point([1, 2, 3]));
+        assert_eq!(Point2D::from(Point([1, 2])), Point2D(1, 2));
+    }
+}
@@ -0,0 +1,226 @@
+use std::ops::{Add, RangeInclusive, Sub};
+
+use itertools::iproduct;
+
+use super::{Point2D, Point3D};
+
+/// The smallest rectangle containing a set of points, both corners inclusive
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Bounds2D<T> {
+    pub min: Point2D<T>,
+    pub max: Point2D<T>,
+}
+
+impl<T: Ord + Copy> Bounds2D<T> {
+    pub fn new(min: Point2D<T>, max: Point2D<T>) -> Self {
+        Self { min, max }
+    }
+
+    /// `None` if there are no points
+    pub fn from_points(points: impl IntoIterator<Item = Point2D<T>>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self::new(point, point))
+            .reduce(|acc, bounds| acc.union(&bounds))
+    }
+
+    pub fn contains(&self, point: &Point2D<T>) -> bool {
+        (self.min.0..=self.max.0).contains(&point.0) && (self.min.1..=self.max.1).contains(&point.1)
+    }
+
+    /// The smallest bounds containing both `self` and `other`
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Point2D(self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: Point2D(self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+
+    /// `None` if the two don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point2D(self.min.0.max(other.min.0), self.min.1.max(other.min.1));
+        let max = Point2D(self.max.0.min(other.max.0), self.max.1.min(other.max.1));
+        (min.0 <= max.0 && min.1 <= max.1).then_some(Self { min, max })
+    }
+}
+
+impl<T> Bounds2D<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + From<u8> + Copy,
+{
+    pub fn width(&self) -> T {
+        self.max.0 - self.min.0 + 1.into()
+    }
+
+    pub fn height(&self) -> T {
+        self.max.1 - self.min.1 + 1.into()
+    }
+}
+
+impl<T: Add<Output = T> + Sub<Output = T> + Copy> Bounds2D<T> {
+    /// Grown by `n` on every side
+    #[must_use]
+    pub fn expand(&self, n: T) -> Self {
+        Self {
+            min: self.min - Point2D(n, n),
+            max: self.max + Point2D(n, n),
+        }
+    }
+}
+
+impl<T: Copy> Bounds2D<T>
+where
+    RangeInclusive<T>: Iterator<Item = T> + Clone,
+{
+    /// Row by row, i.e. `x` changes the fastest
+    pub fn iter_points(&self) -> impl Iterator<Item = Point2D<T>> {
+        iproduct!(self.min.1..=self.max.1, self.min.0..=self.max.0).map(|(y, x)| Point2D(x, y))
+    }
+}
+
+/// The smallest cuboid containing a set of points, both corners inclusive
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Bounds3D<T> {
+    pub min: Point3D<T>,
+    pub max: Point3D<T>,
+}
+
+impl<T: Ord + Copy> Bounds3D<T> {
+    pub fn new(min: Point3D<T>, max: Point3D<T>) -> Self {
+        Self { min, max }
+    }
+
+    /// `None` if there are no points
+    pub fn from_points(points: impl IntoIterator<Item = Point3D<T>>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self::new(point, point))
+            .reduce(|acc, bounds| acc.union(&bounds))
+    }
+
+    pub fn contains(&self, point: &Point3D<T>) -> bool {
+        (self.min.0..=self.max.0).contains(&point.0)
+            && (self.min.1..=self.max.1).contains(&point.1)
+            && (self.min.2..=self.max.2).contains(&point.2)
+    }
+
+    /// The smallest bounds containing both `self` and `other`
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Point3D(
+                self.min.0.min(other.min.0),
+                self.min.1.min(other.min.1),
+                self.min.2.min(other.min.2),
+            ),
+            max: Point3D(
+                self.max.0.max(other.max.0),
+                self.max.1.max(other.max.1),
+                self.max.2.max(other.max.2),
+            ),
+        }
+    }
+
+    /// `None` if the two don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point3D(
+            self.min.0.max(other.min.0),
+            self.min.1.max(other.min.1),
+            self.min.2.max(other.min.2),
+        );
+        let max = Point3D(
+            self.max.0.min(other.max.0),
+            self.max.1.min(other.max.1),
+            self.max.2.min(other.max.2),
+        );
+        (min.0 <= max.0 && min.1 <= max.1 && min.2 <= max.2).then_some(Self { min, max })
+    }
+}
+
+impl<T> Bounds3D<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + From<u8> + Copy,
+{
+    pub fn width(&self) -> T {
+        self.max.0 - self.min.0 + 1.into()
+    }
+
+    pub fn height(&self) -> T {
+        self.max.1 - self.min.1 + 1.into()
+    }
+
+    pub fn depth(&self) -> T {
+        self.max.2 - self.min.2 + 1.into()
+    }
+}
+
+impl<T: Add<Output = T> + Sub<Output = T> + Copy> Bounds3D<T> {
+    /// Grown by `n` on every side
+    #[must_use]
+    pub fn expand(&self, n: T) -> Self {
+        Self {
+            min: self.min - Point3D(n, n, n),
+            max: self.max + Point3D(n, n, n),
+        }
+    }
+}
+
+impl<T: Copy> Bounds3D<T>
+where
+    RangeInclusive<T>: Iterator<Item = T> + Clone,
+{
+    /// Layer by layer and row by row, i.e. `x` changes the fastest
+    pub fn iter_points(&self) -> impl Iterator<Item = Point3D<T>> {
+        iproduct!(
+            self.min.2..=self.max.2,
+            self.min.1..=self.max.1,
+            self.min.0..=self.max.0
+        )
+        .map(|(z, y, x)| Point3D(x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_of_points() {
+        let bounds = Bounds2D::from_points([Point2D(498, 4), Point2D(503, 9), Point2D(494, 6)]);
+        let bounds = bounds.unwrap();
+        assert_eq!(bounds, Bounds2D::new(Point2D(494, 4), Point2D(503, 9)));
+        assert_eq!((bounds.width(), bounds.height()), (10, 6));
+        assert!(bounds.contains(&Point2D(500, 5)));
+        assert!(!bounds.expand(1).contains(&Point2D(500, 11)));
+        assert_eq!(Bounds3D::<i8>::from_points([]), None);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = Bounds3D::new(Point3D(0, 0, 0), Point3D(2, 2, 2));
+        let b = Bounds3D::new(Point3D(1, 1, 1), Point3D(3, 3, 3));
+        let c = Bounds3D::new(Point3D(5, 5, 5), Point3D(6, 6, 6));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Bounds3D::new(Point3D(1, 1, 1), Point3D(2, 2, 2)))
+        );
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(
+            a.union(&c),
+            Bounds3D::new(Point3D(0, 0, 0), Point3D(6, 6, 6))
+        );
+    }
+
+    #[test]
+    fn iter_points() {
+        let bounds = Bounds2D::new(Point2D(0, 0), Point2D(1, 1));
+        let points: Vec<_> = bounds.iter_points().collect();
+        assert_eq!(
+            points,
+            [Point2D(0, 0), Point2D(1, 0), Point2D(0, 1), Point2D(1, 1)]
+        );
+        let bounds = Bounds3D::new(Point3D(0, 0, 0), Point3D(2, 3, 4));
+        assert_eq!(bounds.iter_points().count(), 3 * 4 * 5);
+    }
+}
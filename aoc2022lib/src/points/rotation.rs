@@ -0,0 +1,166 @@
+use std::ops::{Neg, Sub};
+
+use itertools::Itertools;
+
+use super::Point3D;
+
+/// One of the 24 ways to turn a shape around on a 3D grid, without mirroring it.
+/// Coordinate `i` of a rotated point is coordinate `axes[i]` of the original one, times `signs[i]`
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Rotation {
+    axes: [usize; 3],
+    signs: [i8; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Self = Self {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// All 24 orientations, starting with [`Rotation::IDENTITY`]
+    pub fn all() -> impl Iterator<Item = Self> {
+        [0, 1, 2]
+            .into_iter()
+            .permutations(3)
+            .cartesian_product(
+                (0..8).map(|bits| [0, 1, 2].map(|axis| 1 - 2 * ((bits >> axis) & 1))),
+            )
+            .map(|(axes, signs)| Self {
+                axes: [axes[0], axes[1], axes[2]],
+                signs,
+            })
+            // mirroring flips the handedness, i.e. the sign of the determinant
+            .filter(|rotation| rotation.determinant() == 1)
+    }
+
+    fn determinant(self) -> i8 {
+        let [a, b, c] = self.axes;
+        let num_inversions = [a > b, a > c, b > c].into_iter().filter(|&x| x).count();
+        let permutation_sign = if num_inversions % 2 == 0 { 1 } else { -1 };
+        permutation_sign * self.signs.iter().product::<i8>()
+    }
+
+    /// The rotation which applies `first` and then `self`
+    pub fn after(self, first: Self) -> Self {
+        Self {
+            axes: self.axes.map(|axis| first.axes[axis]),
+            signs: [0, 1, 2].map(|i| self.signs[i] * first.signs[self.axes[i]]),
+        }
+    }
+
+    pub fn inverse(self) -> Self {
+        let mut inverse = Self::IDENTITY;
+        for (i, (axis, sign)) in self.axes.into_iter().zip(self.signs).enumerate() {
+            inverse.axes[axis] = i;
+            inverse.signs[axis] = sign;
+        }
+        inverse
+    }
+
+    pub fn apply<T: Neg<Output = T> + Copy>(self, Point3D(x, y, z): Point3D<T>) -> Point3D<T> {
+        let coords = [x, y, z];
+        let [x, y, z] = [0, 1, 2].map(|i| match self.signs[i] {
+            1 => coords[self.axes[i]],
+            _ => -coords[self.axes[i]],
+        });
+        Point3D(x, y, z)
+    }
+
+    pub fn apply_all<T: Neg<Output = T> + Copy>(
+        self,
+        points: impl IntoIterator<Item = Point3D<T>>,
+    ) -> impl Iterator<Item = Point3D<T>> {
+        points.into_iter().map(move |point| self.apply(point))
+    }
+}
+
+/// The orientation-independent form of a shape: of all its rotations, each moved so that its
+/// lowest corner lies at the origin, the one with the smallest sorted list of points.
+/// Two shapes are the same up to rotation (and translation) if their canonical forms are equal
+pub fn canonical_orientation<T>(points: &[Point3D<T>]) -> (Rotation, Vec<Point3D<T>>)
+where
+    T: Neg<Output = T> + Sub<Output = T> + Ord + Copy,
+{
+    Rotation::all()
+        .map(|rotation| {
+            let rotated = rotation.apply_all(points.iter().copied()).collect_vec();
+            let corner =
+                rotated
+                    .iter()
+                    .copied()
+                    .reduce(|Point3D(x1, y1, z1), Point3D(x2, y2, z2)| {
+                        Point3D(x1.min(x2), y1.min(y2), z1.min(z2))
+                    });
+            let shape = rotated
+                .into_iter()
+                .map(|point| point - corner.unwrap_or(point))
+                .sorted_unstable()
+                .collect_vec();
+            (rotation, shape)
+        })
+        .min_by(|(_, shape1), (_, shape2)| shape1.cmp(shape2))
+        .expect("there are 24 rotations")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn there_are_24_distinct_rotations() {
+        let point = Point3D(1, 2, 3);
+        let images: HashSet<_> = Rotation::all()
+            .map(|rotation| rotation.apply(point))
+            .collect();
+        assert_eq!(images.len(), 24);
+    }
+
+    #[test]
+    fn group_operations() {
+        let point = Point3D(1, 2, 3);
+        for r1 in Rotation::all() {
+            assert_eq!(r1.inverse().apply(r1.apply(point)), point);
+            assert_eq!(r1.after(r1.inverse()), Rotation::IDENTITY);
+            for r2 in Rotation::all() {
+                assert_eq!(r2.after(r1).apply(point), r2.apply(r1.apply(point)));
+            }
+        }
+    }
+
+    #[test]
+    fn same_shape_up_to_rotation() {
+        let l_shape = [
+            Point3D(0, 0, 0),
+            Point3D(1, 0, 0),
+            Point3D(2, 0, 0),
+            Point3D(2, 1, 0),
+        ];
+        let turned = [
+            Point3D(5, 5, 5),
+            Point3D(5, 5, 6),
+            Point3D(5, 5, 7),
+            Point3D(5, 4, 7),
+        ];
+        let mirrored = [
+            Point3D(0, 0, 0),
+            Point3D(1, 0, 0),
+            Point3D(2, 0, 0),
+            Point3D(2, -1, 0),
+        ];
+        let flat = [
+            Point3D(0, 0, 0),
+            Point3D(1, 0, 0),
+            Point3D(2, 0, 0),
+            Point3D(3, 0, 0),
+        ];
+
+        let (_, canonical) = canonical_orientation(&l_shape);
+        assert_eq!(canonical_orientation(&turned).1, canonical);
+        // in 3D a flat shape can be flipped over, so its mirror image is just a rotation
+        assert_eq!(canonical_orientation(&mirrored).1, canonical);
+        assert_ne!(canonical_orientation(&flat).1, canonical);
+    }
+}
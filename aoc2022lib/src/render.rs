@@ -0,0 +1,286 @@
+use std::{
+    fmt::{Display, Write},
+    hash::Hash,
+    ops::{Add, RangeInclusive, Sub},
+};
+
+use crate::{
+    grid::{RenderOptions, SparseGrid},
+    points::Point2D,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+
+    fn is_bright(self) -> bool {
+        let Self(r, g, b) = self;
+        u16::from(r) + u16::from(g) + u16::from(b) > 3 * 127
+    }
+}
+
+/// How a kind of cell looks, as text and in colour
+pub trait Pixel {
+    fn glyph(&self) -> char;
+
+    fn colour(&self) -> Rgb;
+}
+
+/// `#` for lit cells, `.` for dark ones
+impl Pixel for bool {
+    fn glyph(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+
+    fn colour(&self) -> Rgb {
+        if *self {
+            Rgb::WHITE
+        } else {
+            Rgb::BLACK
+        }
+    }
+}
+
+/// Dark for `.` and whitespace, lit for anything else
+impl Pixel for char {
+    fn glyph(&self) -> char {
+        *self
+    }
+
+    fn colour(&self) -> Rgb {
+        (*self != '.' && !self.is_whitespace()).colour()
+    }
+}
+
+/// An empty cell is a dark `.`
+impl<T: Pixel> Pixel for Option<T> {
+    fn glyph(&self) -> char {
+        self.as_ref().map_or('.', Pixel::glyph)
+    }
+
+    fn colour(&self) -> Rgb {
+        self.as_ref().map_or(Rgb::BLACK, Pixel::colour)
+    }
+}
+
+/// A rectangle of cells, the top row first
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Canvas<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Canvas<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// `None` if the rows aren't all equally wide
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Option<Self> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for row in rows {
+            let len_before = cells.len();
+            cells.extend(row);
+            let row_width = cells.len() - len_before;
+            if *width.get_or_insert(row_width) != row_width {
+                return None;
+            }
+        }
+        Some(Self {
+            width: width.unwrap_or_default(),
+            cells,
+        })
+    }
+
+    /// What `render_cell` makes of each cell of `grid` in the window `options` describe
+    pub fn from_grid<'g, U, C>(
+        grid: &'g SparseGrid<U, C>,
+        options: &RenderOptions<C>,
+        mut render_cell: impl FnMut(Option<&'g U>) -> T,
+    ) -> Self
+    where
+        C: Add<Output = C> + Sub<Output = C> + Ord + Hash + Copy,
+        RangeInclusive<C>: DoubleEndedIterator<Item = C>,
+    {
+        let Some(window) = options.viewport.or(grid.bounds()) else {
+            return Self {
+                width: 0,
+                cells: Vec::new(),
+            };
+        };
+        let window = window.expand(options.padding);
+        let rows: Box<dyn Iterator<Item = C>> = if options.y_up {
+            Box::new((window.min.1..=window.max.1).rev())
+        } else {
+            Box::new(window.min.1..=window.max.1)
+        };
+        Self::from_rows(rows.map(|y| {
+            (window.min.0..=window.max.0)
+                .map(|x| render_cell(grid.get(&Point2D(x, y))))
+                .collect::<Vec<_>>()
+        }))
+        .expect("all rows span the window")
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or_default()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width).then(|| self.cells.get(y * self.width + x))?
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        assert!(x < self.width, "{x} is outside the canvas");
+        self.cells[y * self.width + x] = value;
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The same picture, with each cell turned into something else, e.g. a [`Pixel`]
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Canvas<U> {
+        Canvas {
+            width: self.width,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Pixel> Canvas<T> {
+    /// Each cell coloured in, for a terminal supporting 24-bit colour
+    pub fn to_ansi(&self) -> String {
+        let mut res = String::new();
+        for row in self.rows() {
+            let mut current = None;
+            for cell in row {
+                let colour = cell.colour();
+                if current != Some(colour) {
+                    let Rgb(r, g, b) = colour;
+                    write!(res, "\x1b[38;2;{r};{g};{b}m").expect("writing to a string");
+                    current = Some(colour);
+                }
+                res.push(cell.glyph());
+            }
+            res.push_str("\x1b[0m\n");
+        }
+        res
+    }
+
+    /// A plain PBM image, with the lit cells black on white like ink on paper
+    pub fn to_pbm(&self) -> Vec<u8> {
+        let mut res = format!("P1\n{} {}\n", self.width(), self.height());
+        for row in self.rows() {
+            let bits: Vec<&str> = row
+                .iter()
+                .map(|cell| if cell.colour().is_bright() { "1" } else { "0" })
+                .collect();
+            res.push_str(&bits.join(" "));
+            res.push('\n');
+        }
+        res.into_bytes()
+    }
+
+    /// A binary PPM image, each cell `scale` pixels wide and tall
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let mut res = format!(
+            "P6\n{} {}\n255\n",
+            self.width() * scale,
+            self.height() * scale
+        )
+        .into_bytes();
+        for row in self.rows() {
+            for _ in 0..scale {
+                for cell in row {
+                    let Rgb(r, g, b) = cell.colour();
+                    for _ in 0..scale {
+                        res.extend([r, g, b]);
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+/// The glyphs, one line per row
+impl<T: Pixel> Display for Canvas<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                f.write_char('\n')?;
+            }
+            for cell in row {
+                f.write_char(cell.glyph())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas<bool> {
+        Canvas::from_rows([[true, false, false], [false, true, true]]).unwrap()
+    }
+
+    #[test]
+    fn text() {
+        let canvas = canvas();
+        assert_eq!((canvas.width(), canvas.height()), (3, 2));
+        assert_eq!(canvas.to_string(), "#..\n.##");
+        assert_eq!(
+            canvas.map(|&lit| if lit { 'o' } else { ' ' }).to_string(),
+            "o  \n oo"
+        );
+        assert_eq!(Canvas::from_rows([vec![1], vec![1, 2]]), None);
+    }
+
+    #[test]
+    fn ansi() {
+        let ansi = canvas().to_ansi();
+        let first_row = ansi.lines().next().unwrap();
+        assert_eq!(
+            first_row,
+            "\x1b[38;2;255;255;255m#\x1b[38;2;0;0;0m..\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn images() {
+        assert_eq!(canvas().to_pbm(), b"P1\n3 2\n1 0 0\n0 1 1\n");
+        let ppm = canvas().to_ppm(2);
+        let header = b"P6\n6 4\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 6 * 4 * 3);
+        assert_eq!(ppm[header.len()..header.len() + 6], [255; 6]);
+    }
+
+    #[test]
+    fn from_grid() {
+        let grid: SparseGrid<()> = [Point2D(-1, -1), Point2D(0, 0)].into_iter().collect();
+        let canvas = Canvas::from_grid(&grid, &RenderOptions::default(), |cell| cell.is_some());
+        assert_eq!(canvas.to_string(), "#.\n.#");
+    }
+}
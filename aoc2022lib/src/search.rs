@@ -0,0 +1,273 @@
+use std::hash::Hash;
+
+use crate::collections::{FastMap, FastSet};
+
+/// An optimisation puzzle: out of all the states reachable from the start, find the most valuable
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+    type Decision: Clone;
+    type Value: Copy + Ord;
+
+    /// What ending up in `state` is worth
+    fn value(&self, state: &Self::State) -> Self::Value;
+
+    /// The states `state` can go on to, along with the decision leading to each
+    fn moves(&self, state: &Self::State) -> Vec<(Self::Decision, Self::State)>;
+
+    /// Something no state reachable from `state` is worth more than, if it's known
+    fn upper_bound(&self, _state: &Self::State) -> Option<Self::Value> {
+        None
+    }
+
+    /// States only get checked for [`SearchProblem::dominates`] against others with the same key
+    fn dominance_key(&self, _state: &Self::State) -> Option<u64> {
+        None
+    }
+
+    /// Whether `state` is at least as good as `other` in every way, so `other` needn't be explored
+    fn dominates(&self, _state: &Self::State, _other: &Self::State) -> bool {
+        false
+    }
+}
+
+/// The most valuable state found, and how to get there from the start
+#[derive(Clone, Debug)]
+pub struct Best<D, V> {
+    pub value: V,
+    pub decisions: Vec<D>,
+    /// How many states were explored
+    pub num_visited: usize,
+}
+
+struct Dfs<'p, P: SearchProblem> {
+    problem: &'p P,
+    /// States already explored, the best way on from each has been found then
+    seen: FastSet<P::State>,
+    /// The states no other seen state dominates, by their dominance key
+    undominated: FastMap<u64, Vec<P::State>>,
+    best: Best<P::Decision, P::Value>,
+    decisions: Vec<P::Decision>,
+}
+
+impl<P: SearchProblem> Dfs<'_, P> {
+    fn is_dominated(&mut self, state: &P::State) -> bool {
+        let Some(key) = self.problem.dominance_key(state) else {
+            return false;
+        };
+        let others = self.undominated.entry(key).or_default();
+        if others
+            .iter()
+            .any(|other| self.problem.dominates(other, state))
+        {
+            return true;
+        }
+        others.retain(|other| !self.problem.dominates(state, other));
+        others.push(state.clone());
+        false
+    }
+
+    fn explore(&mut self, state: &P::State) {
+        if !self.seen.insert(state.clone()) || self.is_dominated(state) {
+            return;
+        }
+        self.best.num_visited += 1;
+
+        let value = self.problem.value(state);
+        if value > self.best.value {
+            self.best.value = value;
+            self.best.decisions.clone_from(&self.decisions);
+        }
+        if let Some(bound) = self.problem.upper_bound(state) {
+            if bound <= self.best.value {
+                return;
+            }
+        }
+
+        for (decision, next) in self.problem.moves(state) {
+            self.decisions.push(decision);
+            self.explore(&next);
+            self.decisions.pop();
+        }
+    }
+}
+
+/// Depth-first search, never exploring a state twice
+pub fn best<P: SearchProblem>(problem: &P, start: &P::State) -> Best<P::Decision, P::Value> {
+    best_after(problem, start, Vec::new())
+}
+
+/// Like [`best`], starting from the state `decisions` lead to
+fn best_after<P: SearchProblem>(
+    problem: &P,
+    start: &P::State,
+    decisions: Vec<P::Decision>,
+) -> Best<P::Decision, P::Value> {
+    let mut dfs = Dfs {
+        problem,
+        seen: FastSet::default(),
+        undominated: FastMap::default(),
+        best: Best {
+            value: problem.value(start),
+            decisions: decisions.clone(),
+            num_visited: 0,
+        },
+        decisions,
+    };
+    dfs.explore(start);
+    dfs.best
+}
+
+/// Like [`best`], exploring what each first move leads to on a thread of its own.
+/// The threads don't share what they've seen, so some states may be explored more than once
+#[cfg(feature = "rayon")]
+pub fn best_parallel<P>(problem: &P, start: &P::State) -> Best<P::Decision, P::Value>
+where
+    P: SearchProblem + Sync,
+    P::State: Send + Sync,
+    P::Decision: Send,
+    P::Value: Send,
+{
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    let best_after_first_move = problem
+        .moves(start)
+        .into_par_iter()
+        .map(|(decision, next)| best_after(problem, &next, vec![decision]))
+        .reduce_with(|a, b| {
+            let num_visited = a.num_visited + b.num_visited;
+            let best = if b.value > a.value { b } else { a };
+            Best {
+                num_visited,
+                ..best
+            }
+        });
+
+    let start_value = problem.value(start);
+    match best_after_first_move {
+        Some(best) if best.value > start_value => Best {
+            num_visited: best.num_visited + 1,
+            ..best
+        },
+        _ => Best {
+            value: start_value,
+            decisions: Vec::new(),
+            num_visited: best_after_first_move.map_or(1, |best| best.num_visited + 1),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0/1 knapsack: each state is how many items have been decided on and the weight so far
+    struct Knapsack {
+        items: Vec<(u32, u32)>,
+        capacity: u32,
+    }
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Packing {
+        next_item: usize,
+        weight: u32,
+        value: u32,
+    }
+
+    impl SearchProblem for Knapsack {
+        type State = Packing;
+        type Decision = bool;
+        type Value = u32;
+
+        fn value(&self, state: &Packing) -> u32 {
+            state.value
+        }
+
+        fn moves(&self, state: &Packing) -> Vec<(bool, Packing)> {
+            let Some(&(weight, value)) = self.items.get(state.next_item) else {
+                return Vec::new();
+            };
+            let skip = Packing {
+                next_item: state.next_item + 1,
+                ..state.clone()
+            };
+            let mut moves = vec![(false, skip)];
+            if state.weight + weight <= self.capacity {
+                let take = Packing {
+                    next_item: state.next_item + 1,
+                    weight: state.weight + weight,
+                    value: state.value + value,
+                };
+                moves.push((true, take));
+            }
+            moves
+        }
+
+        fn upper_bound(&self, state: &Packing) -> Option<u32> {
+            let rest: u32 = self.items[state.next_item..]
+                .iter()
+                .map(|(_, value)| value)
+                .sum();
+            Some(state.value + rest)
+        }
+
+        fn dominance_key(&self, state: &Packing) -> Option<u64> {
+            Some(state.next_item as u64)
+        }
+
+        fn dominates(&self, state: &Packing, other: &Packing) -> bool {
+            state.weight <= other.weight && state.value >= other.value
+        }
+    }
+
+    fn knapsack() -> Knapsack {
+        Knapsack {
+            items: vec![(5, 10), (4, 40), (6, 30), (3, 50)],
+            capacity: 10,
+        }
+    }
+
+    const START: Packing = Packing {
+        next_item: 0,
+        weight: 0,
+        value: 0,
+    };
+
+    #[test]
+    fn best_value_and_decisions() {
+        let best = best(&knapsack(), &START);
+        assert_eq!(best.value, 90);
+        assert_eq!(best.decisions, [false, true, false, true]);
+    }
+
+    #[test]
+    fn pruning_visits_fewer_states() {
+        struct Unpruned(Knapsack);
+
+        impl SearchProblem for Unpruned {
+            type State = Packing;
+            type Decision = bool;
+            type Value = u32;
+
+            fn value(&self, state: &Packing) -> u32 {
+                self.0.value(state)
+            }
+
+            fn moves(&self, state: &Packing) -> Vec<(bool, Packing)> {
+                self.0.moves(state)
+            }
+        }
+
+        let pruned = best(&knapsack(), &START);
+        let unpruned = best(&Unpruned(knapsack()), &START);
+        assert_eq!(pruned.value, unpruned.value);
+        assert!(pruned.num_visited < unpruned.num_visited);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel() {
+        let best = best_parallel(&knapsack(), &START);
+        assert_eq!(best.value, 90);
+        assert_eq!(best.decisions, [false, true, false, true]);
+    }
+}
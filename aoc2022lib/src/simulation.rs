@@ -0,0 +1,174 @@
+/// Something that changes one step at a time
+pub trait Simulation {
+    /// What gets recorded of each step
+    type Snapshot;
+    /// Why a step couldn't be taken, [`std::convert::Infallible`] if it always can
+    type Error;
+
+    fn step(&mut self) -> Result<(), Self::Error>;
+
+    /// Whether there's nothing left to simulate. Never, unless told otherwise
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    fn snapshot(&self) -> Self::Snapshot;
+}
+
+/// Steps a [`Simulation`] along, counting the steps and optionally recording a history
+pub struct Driver<S: Simulation> {
+    simulation: S,
+    steps: usize,
+    history: Option<Vec<S::Snapshot>>,
+}
+
+impl<S: Simulation> Driver<S> {
+    pub fn new(simulation: S) -> Self {
+        Self {
+            simulation,
+            steps: 0,
+            history: None,
+        }
+    }
+
+    /// Snapshots the simulation as it is now and after every step
+    pub fn recording(simulation: S) -> Self {
+        let history = vec![simulation.snapshot()];
+        Self {
+            simulation,
+            steps: 0,
+            history: Some(history),
+        }
+    }
+
+    pub fn simulation(&self) -> &S {
+        &self.simulation
+    }
+
+    pub fn into_simulation(self) -> S {
+        self.simulation
+    }
+
+    /// How many steps have been taken so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// `false` if the simulation was already done. A step that fails isn't counted
+    pub fn step(&mut self) -> Result<bool, S::Error> {
+        if self.simulation.is_done() {
+            return Ok(false);
+        }
+        self.simulation.step()?;
+        self.steps += 1;
+        if let Some(history) = &mut self.history {
+            history.push(self.simulation.snapshot());
+        }
+        Ok(true)
+    }
+
+    /// Until `step` steps have been taken, or the simulation is done if that's sooner.
+    /// Stops at the first step that fails
+    pub fn run_to(&mut self, step: usize) -> Result<&S, S::Error> {
+        self.run_to_with(step, |_, _| {})
+    }
+
+    /// Like [`Driver::run_to`], handing the simulation to `on_step` after every step,
+    /// along with how many steps have been taken, e.g. to draw it
+    pub fn run_to_with(
+        &mut self,
+        step: usize,
+        mut on_step: impl FnMut(usize, &S),
+    ) -> Result<&S, S::Error> {
+        while self.steps < step && self.step()? {
+            on_step(self.steps, &self.simulation);
+        }
+        Ok(&self.simulation)
+    }
+
+    /// Until the simulation is done, which for some simulations is never
+    pub fn run(&mut self) -> Result<&S, S::Error> {
+        self.run_to(usize::MAX)
+    }
+
+    /// The snapshots recorded so far, the one from before the first step first.
+    /// Empty unless the driver is [`Driver::recording`]
+    pub fn history(&self) -> &[S::Snapshot] {
+        self.history.as_deref().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    /// Counts down to 0
+    struct Countdown(u32);
+
+    impl Simulation for Countdown {
+        type Snapshot = u32;
+        type Error = Infallible;
+
+        fn step(&mut self) -> Result<(), Infallible> {
+            self.0 -= 1;
+            Ok(())
+        }
+
+        fn is_done(&self) -> bool {
+            self.0 == 0
+        }
+
+        fn snapshot(&self) -> u32 {
+            self.0
+        }
+    }
+
+    /// Fails once it's burnt down
+    struct Fuse(u32);
+
+    impl Simulation for Fuse {
+        type Snapshot = u32;
+        type Error = &'static str;
+
+        fn step(&mut self) -> Result<(), &'static str> {
+            self.0 = self.0.checked_sub(1).ok_or("burnt down")?;
+            Ok(())
+        }
+
+        fn snapshot(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn run_to_and_until_done() {
+        let mut driver = Driver::new(Countdown(5));
+        assert_eq!(driver.run_to(2).unwrap().0, 3);
+        assert_eq!(driver.run().unwrap().0, 0);
+        assert_eq!(driver.steps(), 5);
+        assert_eq!(driver.step(), Ok(false));
+        assert_eq!(driver.run_to(10).unwrap().0, 0);
+        assert!(driver.history().is_empty());
+    }
+
+    #[test]
+    fn stops_at_a_failed_step() {
+        let mut driver = Driver::recording(Fuse(2));
+        assert_eq!(driver.run().err(), Some("burnt down"));
+        assert_eq!(driver.steps(), 2);
+        assert_eq!(driver.history(), [2, 1, 0]);
+    }
+
+    #[test]
+    fn history_and_frames() {
+        let mut driver = Driver::recording(Countdown(3));
+        let mut frames = Vec::new();
+        let _ = driver.run_to_with(usize::MAX, |step, countdown| {
+            frames.push(format!("{step}: {}", countdown.0));
+        });
+        assert_eq!(driver.history(), [3, 2, 1, 0]);
+        assert_eq!(frames, ["1: 2", "2: 1", "3: 0"]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Reading inputs a line at a time.
+//!
+//! The days that can be solved without holding their whole input in memory have
+//! `p1_from_reader` and `p2_from_reader` next to `p1` and `p2`. They take a [`BufRead`] rather
+//! than a `&str` and give the same answers as `p1` and `p2` on the same input.
+
+use std::io::BufRead;
+
+use crate::Result;
+
+/// Calls `on_line` with each line of `reader` and its number, counting from 1, without the line
+/// ending. Only one line is kept in memory at a time
+pub fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut on_line: impl FnMut(usize, &str) -> Result<()>,
+) -> Result<()> {
+    let mut line = String::new();
+    let mut line_num = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        line_num += 1;
+        let content = line.strip_suffix('\n').unwrap_or(&line);
+        let content = content.strip_suffix('\r').unwrap_or(content);
+        on_line(line_num, content)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AocError;
+
+    #[test]
+    fn lines() {
+        let mut lines = Vec::new();
+        for_each_line("a\r\n\nbc".as_bytes(), |num, line| {
+            lines.push((num, line.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            lines,
+            [(1, "a".into()), (2, String::new()), (3, "bc".into())]
+        );
+
+        let stopped = for_each_line("a\nb".as_bytes(), |num, _| {
+            if num == 2 {
+                Err(AocError::NoSolution)
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(stopped, Err(AocError::NoSolution));
+        assert!(matches!(
+            for_each_line(&[0xff, b'\n'][..], |_, _| Ok(())),
+            Err(AocError::Io(_))
+        ));
+    }
+}
@@ -1,32 +1,101 @@
-use anyhow::Context;
+use std::io::BufRead;
+
+use aoc2022lib::{math::CheckedFold, parse::ParseError, stream::for_each_line, AocError};
 use itertools::Itertools;
 
-pub fn p1(file: &str) -> anyhow::Result<u32> {
-    // split elves inventories
-    file.split("\n\n")
-        // calculate each elf's total calories
-        .map(|elf_inventory| {
-            elf_inventory
-                .lines()
-                .map(|line| line.parse::<u32>().unwrap())
-                .sum()
-        })
-        .max()
-        .context("No goblins")
-}
-
-pub fn p2(file: &str) -> u32 {
-    // split elves inventories
-    file.split("\n\n")
-        // calculate each elf's total calories
-        .map(|elf_inventory| {
-            elf_inventory
-                .lines()
-                .map(|line| line.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
+/// How many calories each elf is carrying. Empty input is a single elf carrying nothing
+pub struct Elves(Vec<u32>);
+
+pub fn parse(file: &str) -> aoc2022lib::Result<Elves> {
+    let mut elves_calories = Vec::new();
+    for_each_elf(file.as_bytes(), |calories| elves_calories.push(calories))?;
+    Ok(Elves(elves_calories))
+}
+
+pub fn part1(Elves(elves_calories): &Elves) -> aoc2022lib::Result<u32> {
+    Ok(elves_calories.iter().copied().max().unwrap_or_default())
+}
+
+pub fn part2(Elves(elves_calories): &Elves) -> aoc2022lib::Result<u32> {
+    elves_calories
+        .iter()
+        .copied()
         .sorted_unstable()
         .rev()
         .take(3)
-        .sum()
+        .checked_sum()
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
+}
+
+/// Calls `on_elf` with each elf's total calories, reading the inventories a line at a time
+fn for_each_elf<R: BufRead>(reader: R, mut on_elf: impl FnMut(u32)) -> aoc2022lib::Result<()> {
+    let mut elf_calories = None;
+    for_each_line(reader, |line_num, line| {
+        // an empty line ends an elf's inventory
+        if line.is_empty() {
+            on_elf(elf_calories.take().unwrap_or_default());
+            return Ok(());
+        }
+        let calories = line
+            .parse::<u32>()
+            .map_err(|err| ParseError::new(line, err).on_line(line_num))?;
+        let total = elf_calories.unwrap_or_default();
+        elf_calories = Some(total.checked_add(calories).ok_or(AocError::Overflow)?);
+        Ok(())
+    })?;
+    on_elf(elf_calories.unwrap_or_default());
+    Ok(())
+}
+
+/// The most calories any elf carries, reading the inventories an elf at a time
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<u32> {
+    let mut max = 0;
+    for_each_elf(reader, |calories| max = max.max(calories))?;
+    Ok(max)
+}
+
+/// The calories the top three elves carry, keeping only those three totals in memory
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<u32> {
+    // the smallest first
+    let mut top_three = [0; 3];
+    for_each_elf(reader, |calories| {
+        if calories > top_three[0] {
+            top_three[0] = calories;
+            top_three.sort_unstable();
+        }
+    })?;
+    top_three.into_iter().checked_sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn test_p1() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p1(&inp).unwrap(), 24000);
+        assert_eq!(p1_from_reader(inp.as_bytes()).unwrap(), 24000);
+    }
+    #[test]
+    fn test_p2() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p2(&inp).unwrap(), 45000);
+        assert_eq!(p2_from_reader(inp.as_bytes()).unwrap(), 45000);
+    }
+    #[test]
+    fn empty_input() {
+        assert_eq!(p1(""), Ok(0));
+        assert_eq!(p2(""), Ok(0));
+        assert_eq!(p1_from_reader("".as_bytes()), Ok(0));
+        assert_eq!(p2_from_reader("".as_bytes()), Ok(0));
+    }
 }
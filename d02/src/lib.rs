@@ -1,44 +1,109 @@
-pub fn p2(file: &str) -> u32 {
-    file.lines()
-        // parse a round as pair of moves
-        .map(|round| round.split_once(' ').unwrap())
-        .map(|(opp_move, your_move)| {
-            // score based on what you played
-            (match (opp_move, your_move) {
-                ("A", "Y") | ("B", "X") | ("C", "Z") => 1,
-                ("A", "Z") | ("B", "Y") | ("C", "X") => 2,
-                ("A", "X") | ("B", "Z") | ("C", "Y") => 3,
-                _ => unreachable!(),
-            })
-            // score based on round outcome
-            + match your_move {
-                "X" => 0,
-                "Y" => 3,
-                "Z" => 6,
-                _ => unreachable!(),
-            }
-        })
-        .sum()
-}
-
-pub fn p1(file: &str) -> u32 {
-    file.lines()
-        .map(|round| round.split_once(' ').unwrap())
-        .map(|(opp_move, your_move)| {
-            // score based on round outcome
-            (match (opp_move, your_move) {
-            ("A", "Z") | ("B", "X") | ("C", "Y") => 0,
-            ("A", "X") | ("B", "Y") | ("C", "Z") => 3,
-            ("A", "Y") | ("B", "Z") | ("C", "X") => 6,
-            _ => unreachable!(),
-        })
-        // score based on what you played
-        + match your_move {
-            "X" => 1,
-            "Y" => 2,
-            "Z" => 3,
-            _ => unreachable!(),
-        }
-        })
-        .sum()
+use std::io::BufRead;
+
+use aoc2022lib::{math::CheckedFold, parse::ParseError, stream::for_each_line, AocError};
+
+/// The opponent's move and what the second column says for each round
+pub struct StrategyGuide<'a>(Vec<(&'a str, &'a str)>);
+
+// A Y
+fn round(round: &str) -> Result<(&str, &str), ParseError> {
+    // parse a round as pair of moves
+    match round.split_once(' ') {
+        Some(moves @ ("A" | "B" | "C", "X" | "Y" | "Z")) => Ok(moves),
+        _ => Err(ParseError::new(round, "not a round of two moves")),
+    }
+}
+
+pub fn parse(file: &str) -> aoc2022lib::Result<StrategyGuide<'_>> {
+    let rounds = file
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| round(line).map_err(|err| err.on_line(idx + 1)));
+    Ok(StrategyGuide(rounds.collect::<Result<_, _>>()?))
+}
+
+fn score_p1((opp_move, your_move): (&str, &str)) -> u32 {
+    // score based on round outcome
+    (match (opp_move, your_move) {
+        ("A", "Z") | ("B", "X") | ("C", "Y") => 0,
+        ("A", "X") | ("B", "Y") | ("C", "Z") => 3,
+        _ => 6,
+    })
+    // score based on what you played
+    + match your_move {
+        "X" => 1,
+        "Y" => 2,
+        _ => 3,
+    }
+}
+
+pub fn part1(StrategyGuide(rounds): &StrategyGuide) -> aoc2022lib::Result<u32> {
+    rounds.iter().copied().map(score_p1).checked_sum()
+}
+
+fn score_p2((opp_move, your_move): (&str, &str)) -> u32 {
+    // score based on what you played
+    (match (opp_move, your_move) {
+        ("A", "Y") | ("B", "X") | ("C", "Z") => 1,
+        ("A", "Z") | ("B", "Y") | ("C", "X") => 2,
+        _ => 3,
+    })
+    // score based on round outcome
+    + match your_move {
+        "X" => 0,
+        "Y" => 3,
+        _ => 6,
+    }
+}
+
+pub fn part2(StrategyGuide(rounds): &StrategyGuide) -> aoc2022lib::Result<u32> {
+    rounds.iter().copied().map(score_p2).checked_sum()
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
+}
+
+/// Adds up the scores of the rounds in `reader`, a line at a time
+fn total_score<R: BufRead>(reader: R, score: fn((&str, &str)) -> u32) -> aoc2022lib::Result<u32> {
+    let mut total = 0u32;
+    for_each_line(reader, |line_num, line| {
+        let round = round(line).map_err(|err| err.on_line(line_num))?;
+        total = total.checked_add(score(round)).ok_or(AocError::Overflow)?;
+        Ok(())
+    })?;
+    Ok(total)
+}
+
+/// The total score if the second column is what to play, reading a round at a time
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<u32> {
+    total_score(reader, score_p1)
+}
+
+/// The total score if the second column is how the round ends, reading a round at a time
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<u32> {
+    total_score(reader, score_p2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn test_p1() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p1(&inp).unwrap(), 15);
+        assert_eq!(p1_from_reader(inp.as_bytes()).unwrap(), 15);
+    }
+    #[test]
+    fn test_p2() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p2(&inp).unwrap(), 12);
+        assert_eq!(p2_from_reader(inp.as_bytes()).unwrap(), 12);
+    }
 }
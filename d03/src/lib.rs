@@ -1,47 +1,150 @@
-use std::collections::HashSet;
+use std::io::BufRead;
 
-use intersection::hash_set;
+use aoc2022lib::{
+    bitset::BitSet, math::CheckedFold, parse::ParseError, stream::for_each_line, AocError,
+};
 use itertools::Itertools;
 
-fn to_priority(char: char) -> usize {
-    match char {
-        'a'..='z' => (char as usize) - 96,
-        'A'..='Z' => (char as usize) - 64 + 26,
-        _ => unreachable!(),
+/// Items are kept as their priorities, `a..=z` being `1..=26` and `A..=Z` being `27..=52`
+type Items = BitSet;
+
+/// Each rucksack's items, as written
+pub struct Rucksacks<'a>(Vec<&'a str>);
+
+// vJrwpWtwJgWrhcsFMMfFFhFp
+fn rucksack(rucksack: &str) -> Result<&str, ParseError> {
+    match rucksack.find(|item: char| !item.is_ascii_alphabetic()) {
+        Some(column) => Err(ParseError::at(rucksack, &rucksack[column..], "not an item")),
+        None => Ok(rucksack),
     }
 }
 
-pub fn p1(file: &str) -> usize {
-    file.lines()
-        // split into compartments
-        .map(|rucksack| rucksack.split_at(rucksack.len() / 2))
-        .map(|(compartment1, compartment2)| (compartment1, compartment2).into())
-        // find the common item
-        .map(|compartments: [&str; 2]| {
-            compartments.map(|compartment| compartment.chars().collect::<HashSet<char>>())
-        })
-        .map(|[compartment1, compartment2]| {
-            *compartment1
-                .intersection(&compartment2)
-                .exactly_one()
-                .unwrap()
-        })
-        // calculate its priority
-        .map(to_priority)
+pub fn parse(file: &str) -> aoc2022lib::Result<Rucksacks<'_>> {
+    let rucksacks = file
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| rucksack(line).map_err(|err| err.on_line(idx + 1)))
+        .collect::<Result<_, _>>()?;
+    Ok(Rucksacks(rucksacks))
+}
+
+fn items(items: &str) -> aoc2022lib::Result<Items> {
+    Items::try_from_letters(items.chars()).ok_or_else(|| AocError::invalid_input("not an item"))
+}
+
+fn the_only_item(items: Items) -> aoc2022lib::Result<usize> {
+    items
+        .iter()
+        .exactly_one()
+        .map_err(|_| AocError::invalid_input("not exactly one item in common"))
+}
+
+/// The priority of the item in both compartments
+fn misplaced_item(rucksack: &str) -> aoc2022lib::Result<usize> {
+    // split into compartments
+    let (compartment1, compartment2) = rucksack.split_at(rucksack.len() / 2);
+    // find the common item
+    the_only_item(items(compartment1)? & items(compartment2)?)
+}
+
+pub fn part1(Rucksacks(rucksacks): &Rucksacks) -> aoc2022lib::Result<usize> {
+    rucksacks
+        .iter()
+        .map(|rucksack| misplaced_item(rucksack))
+        .collect::<aoc2022lib::Result<Vec<_>>>()?
+        .into_iter()
         // add up the priorities
-        .sum()
+        .checked_sum()
 }
 
-pub fn p2(file: &str) -> usize {
-    file.lines()
-        .map(|rucksack| rucksack.chars().collect::<HashSet<char>>())
+pub fn part2(Rucksacks(rucksacks): &Rucksacks) -> aoc2022lib::Result<usize> {
+    let rucksacks = rucksacks
+        .iter()
+        .map(|rucksack| items(rucksack))
+        .collect::<aoc2022lib::Result<Vec<_>>>()?;
+    rucksacks
+        .into_iter()
         // get chunks of 3 backpacks
         .chunks(3)
         .into_iter()
         // in each chunk, find the common item (the badge)
-        .map(|chunk| hash_set::intersection(chunk).drain().exactly_one().unwrap())
-        // calculate its priority
-        .map(to_priority)
+        .map(|chunk| {
+            let common = chunk
+                .reduce(|acc, rucksack| acc & rucksack)
+                .unwrap_or_default();
+            the_only_item(common)
+        })
+        .collect::<aoc2022lib::Result<Vec<_>>>()?
+        .into_iter()
         // add up the priorities
-        .sum()
+        .checked_sum()
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?)
+}
+
+/// The sum of the priorities of the items in both compartments, reading a rucksack at a time
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    let mut total = 0;
+    for_each_line(reader, |line_num, line| {
+        let rucksack = rucksack(line).map_err(|err| err.on_line(line_num))?;
+        total = misplaced_item(rucksack)?
+            .checked_add(total)
+            .ok_or(AocError::Overflow)?;
+        Ok(())
+    })?;
+    Ok(total)
+}
+
+/// The sum of the priorities of the badges, keeping only the current group's common items in
+/// memory
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    let mut total = 0;
+    // the items common to the rucksacks of the current group so far
+    let mut common = None;
+    let mut group_size = 0;
+    for_each_line(reader, |line_num, line| {
+        let rucksack = rucksack(line).map_err(|err| err.on_line(line_num))?;
+        let items = items(rucksack)?;
+        common = Some(common.map_or(items, |common| common & items));
+        group_size += 1;
+        if group_size == 3 {
+            total = the_only_item(common.take().unwrap_or_default())?
+                .checked_add(total)
+                .ok_or(AocError::Overflow)?;
+            group_size = 0;
+        }
+        Ok(())
+    })?;
+    // the last group may be smaller
+    if let Some(common) = common {
+        total = the_only_item(common)?
+            .checked_add(total)
+            .ok_or(AocError::Overflow)?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn test_p1() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p1(&inp).unwrap(), 157);
+        assert_eq!(p1_from_reader(inp.as_bytes()).unwrap(), 157);
+    }
+    #[test]
+    fn test_p2() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p2(&inp).unwrap(), 70);
+        assert_eq!(p2_from_reader(inp.as_bytes()).unwrap(), 70);
+    }
 }
@@ -1,41 +1,111 @@
+use std::io::BufRead;
+
+use aoc2022lib::{interval::Interval, parse::ParseError, stream::for_each_line, AocError};
 use itertools::Itertools;
 
-pub fn p1(file: &str) -> usize {
-    // iterate over input lines
-    file.lines()
-        // parse each line as assignment pairs (represented by a 4-element tuple)
-        .map(|line| -> (u32, u32, u32, u32) {
-            line.split(&[',', '-'][..])
-                .map(|num| num.parse().unwrap())
-                .collect_tuple()
-                .expect("A well structured input")
-        })
-        // retain only the tuples where the exercise condition is met
-        .filter(|&(elf1_start, elf1_end, elf2_start, elf2_end)| {
-            (elf1_start <= elf2_start && elf2_end <= elf1_end)
-                || (elf2_start <= elf1_start && elf1_end <= elf2_end)
+type Assignment = Interval<u32>;
+
+// 2-4,6-8
+fn assignment_pair(line: &str) -> aoc2022lib::Result<(Assignment, Assignment)> {
+    let Some((start1, end1, start2, end2)) = line.split(&[',', '-'][..]).collect_tuple() else {
+        return Err(ParseError::new(line, "not two ranges of sections").into());
+    };
+    let assignment = |start: &str, end: &str| {
+        let start = start.parse().map_err(|err| ParseError::new(start, err))?;
+        let end = end.parse().map_err(|err| ParseError::new(end, err))?;
+        Interval::new(start, end).ok_or_else(|| {
+            AocError::invalid_input(format!("{line}: a range ends before it starts"))
         })
-        // count such tuples
-        .count()
+    };
+    Ok((assignment(start1, end1)?, assignment(start2, end2)?))
 }
 
-pub fn p2(file: &str) -> usize {
+/// The sections each pair of elves was assigned
+pub struct AssignmentPairs(Vec<(Assignment, Assignment)>);
+
+pub fn parse(file: &str) -> aoc2022lib::Result<AssignmentPairs> {
     // iterate over input lines
     file.lines()
-        // parse each line as assignment pairs (represented by a 4-element tuple)
-        .map(|line| -> (u32, u32, u32, u32) {
-            line.split(&[',', '-'][..])
-                .map(|num| num.parse().unwrap())
-                .collect_tuple()
-                .expect("A well structured input")
-        })
-        // retain only the tuples where the exercise condition is met
-        .filter(|&(elf1_start, elf1_end, elf2_start, elf2_end)| {
-            (elf1_start <= elf2_start && elf2_start <= elf1_end)
-                || (elf1_start <= elf2_end && elf2_end <= elf1_end)
-                || (elf2_start <= elf1_start && elf1_start <= elf2_end)
-                || (elf2_start <= elf1_end && elf1_end <= elf2_end)
-        })
-        // count such tuples
-        .count()
+        .enumerate()
+        // parse each line as assignment pairs
+        .map(|(idx, line)| assignment_pair(line).map_err(|err| err.on_line(idx + 1)))
+        .collect::<aoc2022lib::Result<_>>()
+        .map(AssignmentPairs)
+}
+
+fn one_contains_other((elf1, elf2): &(Assignment, Assignment)) -> bool {
+    elf1.contains_interval(elf2) || elf2.contains_interval(elf1)
+}
+
+fn overlap((elf1, elf2): &(Assignment, Assignment)) -> bool {
+    elf1.overlaps(elf2)
+}
+
+pub fn part1(AssignmentPairs(pairs): &AssignmentPairs) -> aoc2022lib::Result<usize> {
+    Ok(pairs
+        .iter()
+        // retain only the pairs where the exercise condition is met
+        .filter(|pair| one_contains_other(pair))
+        // count such pairs
+        .count())
+}
+
+pub fn part2(AssignmentPairs(pairs): &AssignmentPairs) -> aoc2022lib::Result<usize> {
+    Ok(pairs
+        .iter()
+        // retain only the pairs where the exercise condition is met
+        .filter(|pair| overlap(pair))
+        // count such pairs
+        .count())
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?)
+}
+
+/// Counts the pairs in `reader` meeting `condition`, a line at a time
+fn count_pairs<R: BufRead>(
+    reader: R,
+    condition: fn(&(Assignment, Assignment)) -> bool,
+) -> aoc2022lib::Result<usize> {
+    let mut count = 0;
+    for_each_line(reader, |line_num, line| {
+        let pair = assignment_pair(line).map_err(|err| err.on_line(line_num))?;
+        count += usize::from(condition(&pair));
+        Ok(())
+    })?;
+    Ok(count)
+}
+
+/// How many pairs have one assignment containing the other, reading a pair at a time
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    count_pairs(reader, one_contains_other)
+}
+
+/// How many pairs have overlapping assignments, reading a pair at a time
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    count_pairs(reader, overlap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn test_p1() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p1(&inp).unwrap(), 2);
+        assert_eq!(p1_from_reader(inp.as_bytes()).unwrap(), 2);
+    }
+    #[test]
+    fn test_p2() {
+        let inp = read_to_string("inputs/test.txt").unwrap();
+        assert_eq!(p2(&inp).unwrap(), 4);
+        assert_eq!(p2_from_reader(inp.as_bytes()).unwrap(), 4);
+    }
 }
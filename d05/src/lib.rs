@@ -1,7 +1,11 @@
 use std::str::FromStr;
 
-use anyhow::Context;
-use aoc2022lib::{impl_from_str_from_nom_parser, parse::n};
+use aoc2022lib::{
+    impl_from_str_from_nom_parser,
+    parse::{n, Parse},
+    simulation::{Driver, Simulation},
+    AocError,
+};
 use derive_deref::Deref;
 use itertools::Itertools;
 use nom::{
@@ -18,14 +22,25 @@ enum CraneModel {
     CrateMover9000,
     CrateMover9001,
 }
+/// A stack as the procedure numbers it, from 1
+#[derive(Clone, Copy)]
+struct StackNum(usize);
+
+impl StackNum {
+    /// Where the stack is in the warehouse, `None` for stack 0
+    fn index(self) -> Option<usize> {
+        self.0.checked_sub(1)
+    }
+}
+
 struct Rearrangement {
     num_crates_to_move: usize,
-    stack_to_move_to: usize,
-    stack_to_take_from: usize,
+    stack_to_move_to: StackNum,
+    stack_to_take_from: StackNum,
 }
 
-fn stack_idx(i: &str) -> IResult<&str, usize> {
-    map(n, |stack_idx: usize| stack_idx - 1)(i)
+fn stack_num(i: &str) -> IResult<&str, StackNum> {
+    map(n, StackNum)(i)
 }
 
 fn num_crates(i: &str) -> IResult<&str, usize> {
@@ -37,8 +52,8 @@ fn rearrangement(i: &str) -> IResult<&str, Rearrangement> {
     map(
         tuple((
             preceded(tag("move "), num_crates),
-            preceded(tag(" from "), stack_idx),
-            preceded(tag(" to "), stack_idx),
+            preceded(tag(" from "), stack_num),
+            preceded(tag(" to "), stack_num),
         )),
         |(num_crates_to_move, stack_to_take_from, stack_to_move_to)| Rearrangement {
             num_crates_to_move,
@@ -80,10 +95,11 @@ impl_from_str_from_nom_parser!(crate_line, CrateLine);
 
 type Warehouse = Vec<Vec<char>>;
 
-fn warehouse(s: &str) -> anyhow::Result<Warehouse> {
+fn warehouse(s: &str) -> aoc2022lib::Result<Warehouse> {
     // remove the last row of the stack arrangement schema - the one with stack numbers
-    let (initial_stack_arrangement, last_row_of_stack_arrangement) =
-        s.rsplit_once('\n').context("No stack numbers row")?;
+    let (initial_stack_arrangement, last_row_of_stack_arrangement) = s
+        .rsplit_once('\n')
+        .ok_or_else(|| AocError::missing_input("No stack numbers row"))?;
 
     // since we don't need the last row anyway, use it to indirectly calculate the number of stacks
     let num_stacks = (last_row_of_stack_arrangement.len() + 1) / 4;
@@ -94,17 +110,21 @@ fn warehouse(s: &str) -> anyhow::Result<Warehouse> {
 
     // parse the initial stack arrangement - fill up the warehouse
     // comment: go over lines bottom-up, since that's how the crates are stacked
-    for line in initial_stack_arrangement.lines().rev() {
-        CrateLine::from_str(line)?
-            .iter()
-            // provide the stack number for each maybe-crate
-            .enumerate()
+    let lines = initial_stack_arrangement.lines().enumerate().collect_vec();
+    for (line_idx, line) in lines.into_iter().rev() {
+        let crate_line = CrateLine::from_str(line).map_err(|err| err.on_line(line_idx + 1))?;
+        if crate_line.len() > num_stacks {
+            return Err(AocError::invalid_input(format!(
+                "{line:?} has crates in more than {num_stacks} stacks"
+            )));
+        }
+        // provide the stack number for each maybe-crate
+        for (idx, optional_crate) in crate_line.iter().enumerate() {
             // if there's a crate, add it to the corresponding stack, skip if only air
-            .for_each(|(idx, optional_crate)| {
-                if let Some(some_crate) = optional_crate {
-                    stacks[idx].push(some_crate.name);
-                }
-            });
+            if let Some(some_crate) = optional_crate {
+                stacks[idx].push(some_crate.name);
+            }
+        }
     }
     Ok(stacks)
 }
@@ -113,12 +133,26 @@ fn apply_rearrangement(
     warehouse: &mut Warehouse,
     rearrangement: &Rearrangement,
     crane_model: &CraneModel,
-) {
-    let current_length_of_stack_to_move_from = warehouse[rearrangement.stack_to_take_from].len();
+) -> aoc2022lib::Result<()> {
+    let no_such_stack = || AocError::invalid_input("no such stack");
+    let stack_to_move_to = rearrangement
+        .stack_to_move_to
+        .index()
+        .filter(|&stack_idx| stack_idx < warehouse.len())
+        .ok_or_else(no_such_stack)?;
+    let stack_to_take_from = rearrangement
+        .stack_to_take_from
+        .index()
+        .and_then(|stack_idx| warehouse.get_mut(stack_idx))
+        .ok_or_else(no_such_stack)?;
+    let current_length_of_stack_to_move_from = stack_to_take_from.len();
 
     let crates_to_move = {
-        let crates = warehouse[rearrangement.stack_to_take_from]
-            .drain((current_length_of_stack_to_move_from - rearrangement.num_crates_to_move)..);
+        let crates = stack_to_take_from.drain(
+            current_length_of_stack_to_move_from
+                .checked_sub(rearrangement.num_crates_to_move)
+                .ok_or_else(|| AocError::invalid_input("not enough crates to move"))?..,
+        );
 
         match crane_model {
             CraneModel::CrateMover9000 => crates.rev().collect_vec(),
@@ -126,48 +160,115 @@ fn apply_rearrangement(
         }
     };
 
-    warehouse[rearrangement.stack_to_move_to].extend(crates_to_move);
+    warehouse[stack_to_move_to].extend(crates_to_move);
+    Ok(())
 }
 
-fn crates_at_the_top(warehouse: &Warehouse) -> anyhow::Result<String> {
-    warehouse
-        .iter()
-        .map(|stack| stack.last())
-        .collect::<Option<String>>()
-        .context("One or more stack ended up empty")
+/// The starting arrangement and the rearrangements to apply to it
+pub struct Procedure {
+    warehouse: Warehouse,
+    rearrangements: Vec<Rearrangement>,
 }
 
-pub fn p1(file: &str) -> anyhow::Result<String> {
-    let (initial_stack_schema, rearrangements) = file.split_once("\n\n").unwrap();
+pub fn parse(file: &str) -> aoc2022lib::Result<Procedure> {
+    let (initial_stack_schema, rearrangements) = file
+        .split_once("\n\n")
+        .ok_or_else(|| AocError::missing_input("No rearrangements"))?;
+    let first_rearrangement_line = initial_stack_schema.lines().count() + 2;
+
+    let warehouse = warehouse(initial_stack_schema)?;
+    let rearrangements: Vec<Rearrangement> =
+        Vec::parse(rearrangements).map_err(|err| err.on_line(first_rearrangement_line))?;
+    for (idx, rearrangement) in rearrangements.iter().enumerate() {
+        for stack in [
+            rearrangement.stack_to_take_from,
+            rearrangement.stack_to_move_to,
+        ] {
+            if stack
+                .index()
+                .is_none_or(|stack_idx| stack_idx >= warehouse.len())
+            {
+                return Err(AocError::invalid_input(format!(
+                    "line {}: there's no stack {}",
+                    first_rearrangement_line + idx,
+                    stack.0
+                )));
+            }
+        }
+    }
+    Ok(Procedure {
+        warehouse,
+        rearrangements,
+    })
+}
 
-    let mut warehouse = warehouse(initial_stack_schema)?;
+/// The crane working through the rearrangements, one per step
+struct Crane<'a> {
+    model: CraneModel,
+    warehouse: Warehouse,
+    rearrangements: &'a [Rearrangement],
+    num_applied: usize,
+}
+
+impl<'a> Crane<'a> {
+    fn new(procedure: &'a Procedure, model: CraneModel) -> Self {
+        Self {
+            model,
+            warehouse: procedure.warehouse.clone(),
+            rearrangements: &procedure.rearrangements,
+            num_applied: 0,
+        }
+    }
+}
 
-    // apply the rearrangements
-    for rearrangement in rearrangements.lines() {
-        let rearrangement = Rearrangement::from_str(rearrangement)?;
+impl Simulation for Crane<'_> {
+    type Snapshot = Warehouse;
+    type Error = AocError;
 
-        apply_rearrangement(&mut warehouse, &rearrangement, &CraneModel::CrateMover9000);
+    fn step(&mut self) -> aoc2022lib::Result<()> {
+        let rearrangement = &self.rearrangements[self.num_applied];
+        apply_rearrangement(&mut self.warehouse, rearrangement, &self.model)?;
+        self.num_applied += 1;
+        Ok(())
     }
 
-    // get the final arrangement
-    crates_at_the_top(&warehouse)
+    fn is_done(&self) -> bool {
+        self.num_applied == self.rearrangements.len()
+    }
+
+    fn snapshot(&self) -> Warehouse {
+        self.warehouse.clone()
+    }
 }
 
-pub fn p2(file: &str) -> anyhow::Result<String> {
-    let (initial_stack_schema, rearrangements) =
-        file.split_once("\n\n").context("No stack numbers row")?;
+fn crates_at_the_top(warehouse: &Warehouse) -> aoc2022lib::Result<String> {
+    warehouse
+        .iter()
+        .map(|stack| stack.last())
+        .collect::<Option<String>>()
+        .ok_or(AocError::NoSolution)
+}
 
-    let mut warehouse: Warehouse = warehouse(initial_stack_schema)?;
+pub fn part1(procedure: &Procedure) -> aoc2022lib::Result<String> {
+    let crane = Crane::new(procedure, CraneModel::CrateMover9000);
 
-    // apply the rearrangements
-    for rearrangement in rearrangements.lines() {
-        let rearrangement = Rearrangement::from_str(rearrangement)?;
+    // apply the rearrangements, then get the final arrangement
+    crates_at_the_top(&Driver::new(crane).run()?.warehouse)
+}
 
-        apply_rearrangement(&mut warehouse, &rearrangement, &CraneModel::CrateMover9001);
-    }
+pub fn part2(procedure: &Procedure) -> aoc2022lib::Result<String> {
+    let crane = Crane::new(procedure, CraneModel::CrateMover9001);
+
+    // apply the rearrangements, then format the final arrangement
+    crates_at_the_top(&Driver::new(crane).run()?.warehouse)
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<String> {
+    part1(&parse(file)?)
+}
 
-    // format the final arrangement
-    crates_at_the_top(&warehouse)
+pub fn p2(file: &str) -> aoc2022lib::Result<String> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
@@ -175,6 +276,15 @@ mod tests {
     use super::*;
     use std::fs::read_to_string;
 
+    #[test]
+    fn stack_0() {
+        let inp = "    [D]\n[N] [C]\n 1   2 \n\nmove 1 from 0 to 1\n";
+        assert_eq!(
+            p1(inp),
+            Err(AocError::invalid_input("line 5: there's no stack 0"))
+        );
+    }
+
     #[test]
     fn real_p1() {
         let inp = read_to_string("inputs/real.txt").unwrap();
@@ -1,29 +1,85 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, io::BufRead};
+
+use aoc2022lib::{bitset::BitSet, AocError};
+
+fn all_unique<'a>(window: impl IntoIterator<Item = &'a u8>, buffer_size: usize) -> bool {
+    // one bit for every possible byte
+    let letters: BitSet<[u64; 4]> = window.into_iter().copied().map(usize::from).collect();
+    letters.len() == buffer_size
+}
 
 /// Returns the index of the last element in the window,
 /// if such a window exists
-fn get_first_buffer_all_unique(string: &str, buffer_size: usize) -> Option<usize> {
-    let mut buf: VecDeque<char> = VecDeque::with_capacity(buffer_size);
-    let mut chars_to_skip: usize = 0;
-    for (idx, letter) in string.char_indices() {
-        while buf.contains(&letter) {
-            buf.pop_front();
-            chars_to_skip += 1;
+fn get_first_buffer_all_unique(buffer: &[u8], buffer_size: usize) -> Option<usize> {
+    buffer
+        .windows(buffer_size)
+        .position(|window| all_unique(window, buffer_size))
+        .map(|idx| idx + buffer_size)
+}
+
+fn more_than_one_line() -> AocError {
+    AocError::invalid_input("the datastream is more than one line")
+}
+
+/// Like [`get_first_buffer_all_unique`], keeping only the current window in memory
+fn first_buffer_all_unique_from_reader<R: BufRead>(
+    reader: R,
+    buffer_size: usize,
+) -> aoc2022lib::Result<usize> {
+    let mut window = VecDeque::with_capacity(buffer_size);
+    let mut line_ended = false;
+    for (idx, byte) in reader.bytes().enumerate() {
+        let byte = byte?;
+        if matches!(byte, b'\n' | b'\r') {
+            line_ended = true;
+            continue;
+        }
+        if line_ended {
+            return Err(more_than_one_line());
+        }
+        if window.len() == buffer_size {
+            window.pop_front();
         }
-        buf.push_back(letter);
-        chars_to_skip = chars_to_skip.saturating_sub(1);
-        if buf.len() == buffer_size {
-            return Some(idx + 1);
+        window.push_back(byte);
+        if window.len() == buffer_size && all_unique(&window, buffer_size) {
+            return Ok(idx + 1);
         }
     }
-    None
+    Err(AocError::NoSolution)
 }
 
-pub fn p1(buffer: &str) -> Option<usize> {
-    get_first_buffer_all_unique(buffer, 4)
+/// The characters received, as bytes, without the line ending
+pub struct Datastream<'a>(&'a [u8]);
+
+pub fn parse(buffer: &str) -> aoc2022lib::Result<Datastream<'_>> {
+    let buffer = buffer.trim_end_matches(['\n', '\r']);
+    if buffer.contains(['\n', '\r']) {
+        return Err(more_than_one_line());
+    }
+    Ok(Datastream(buffer.as_bytes()))
 }
-pub fn p2(buffer: &str) -> Option<usize> {
-    get_first_buffer_all_unique(buffer, 14)
+
+pub fn part1(Datastream(buffer): &Datastream) -> aoc2022lib::Result<usize> {
+    get_first_buffer_all_unique(buffer, 4).ok_or(AocError::NoSolution)
+}
+pub fn part2(Datastream(buffer): &Datastream) -> aoc2022lib::Result<usize> {
+    get_first_buffer_all_unique(buffer, 14).ok_or(AocError::NoSolution)
+}
+
+pub fn p1(buffer: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(buffer)?)
+}
+pub fn p2(buffer: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(buffer)?)
+}
+
+/// Where the first start-of-packet marker ends, keeping only the last 4 characters in memory
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    first_buffer_all_unique_from_reader(reader, 4)
+}
+/// Where the first start-of-message marker ends, keeping only the last 14 characters in memory
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    first_buffer_all_unique_from_reader(reader, 14)
 }
 
 #[cfg(test)]
@@ -31,12 +87,34 @@ mod tests {
     use super::*;
     use test_case::test_case;
 
-    #[test_case("mjqjpqmgbljsphdztnvjfqwrcgsmlb" => Some(7))]
-    #[test_case("bvwbjplbgvbhsrlpgdmjqwftvncz" => Some(5))]
-    #[test_case("nppdvjthqldpwncqszvftbrmjlhg" => Some(6))]
-    #[test_case("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg" => Some(10))]
-    #[test_case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw" => Some(11))]
-    fn test_p1(inp: &str) -> Option<usize> {
+    #[test_case("mjqjpqmgbljsphdztnvjfqwrcgsmlb" => Ok(7))]
+    #[test_case("bvwbjplbgvbhsrlpgdmjqwftvncz" => Ok(5))]
+    #[test_case("nppdvjthqldpwncqszvftbrmjlhg" => Ok(6))]
+    #[test_case("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg" => Ok(10))]
+    #[test_case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw" => Ok(11))]
+    #[test_case("abca\n" => Err(AocError::NoSolution); "line ending")]
+    #[test_case("ab\ncd" => Err(more_than_one_line()))]
+    fn test_p1(inp: &str) -> aoc2022lib::Result<usize> {
         p1(inp)
     }
+
+    #[test_case("mjqjpqmgbljsphdztnvjfqwrcgsmlb" => Ok(7))]
+    #[test_case("bvwbjplbgvbhsrlpgdmjqwftvncz" => Ok(5))]
+    #[test_case("nppdvjthqldpwncqszvftbrmjlhg" => Ok(6))]
+    #[test_case("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg" => Ok(10))]
+    #[test_case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw" => Ok(11))]
+    #[test_case("abca\n" => Err(AocError::NoSolution); "line ending")]
+    #[test_case("ab\ncd" => Err(more_than_one_line()))]
+    fn test_p1_from_reader(inp: &str) -> aoc2022lib::Result<usize> {
+        p1_from_reader(inp.as_bytes())
+    }
+
+    #[test_case("mjqjpqmgbljsphdztnvjfqwrcgsmlb" => Ok(19))]
+    #[test_case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw" => Ok(26))]
+    #[test_case("abcabc" => Err(AocError::NoSolution))]
+    #[test_case("abcabcabcabcabc\r\n" => Err(AocError::NoSolution); "line ending")]
+    #[test_case("abcabc\nabcdefghijklmn" => Err(more_than_one_line()))]
+    fn test_p2_from_reader(inp: &str) -> aoc2022lib::Result<usize> {
+        p2_from_reader(inp.as_bytes())
+    }
 }
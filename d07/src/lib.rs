@@ -1,13 +1,13 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::path::PathBuf;
 
-use anyhow::Context;
+use aoc2022lib::{collections::FastMap, math::CheckedFold, parse::ParseError, AocError};
 use itertools::Itertools;
 
-type FilesWithSizes = HashMap<PathBuf, u32>;
+type FilesWithSizes = FastMap<PathBuf, u32>;
 
-fn files_with_sizes(s: &str) -> anyhow::Result<FilesWithSizes> {
+fn files_with_sizes(s: &str) -> aoc2022lib::Result<FilesWithSizes> {
     let mut current_path = PathBuf::new();
-    let mut files_with_sizes: FilesWithSizes = HashMap::new();
+    let mut files_with_sizes = FilesWithSizes::default();
 
     for input_and_output in s.split("\n$ ") {
         if let Some(("cd", dir_name)) = input_and_output.split_once(' ') {
@@ -20,70 +20,103 @@ fn files_with_sizes(s: &str) -> anyhow::Result<FilesWithSizes> {
         } else if let Some(("ls", dir_contents)) = input_and_output.split_once('\n') {
             let new_files = dir_contents
                 .lines()
-                .map(|line| line.split_once(' ').context("Invalid `ls` output"))
+                .map(|line| {
+                    line.split_once(' ')
+                        .ok_or_else(|| ParseError::new(line, "Invalid `ls` output"))
+                })
                 .collect::<Result<Vec<_>, _>>()?
                 .into_iter()
                 .filter_map(|(first, second)| match (first, second) {
                     ("dir", _dir_name) => None,
                     (file_size, file_name) => {
                         let file_path = current_path.join(file_name);
-                        let file_size = file_size.parse().unwrap();
-                        Some((file_path, file_size))
+                        let file_size = file_size
+                            .parse()
+                            .map_err(|err| ParseError::new(file_size, err));
+                        Some(file_size.map(|file_size| (file_path, file_size)))
                     }
-                });
+                })
+                .collect::<Result<Vec<_>, _>>()?;
             files_with_sizes.extend(new_files);
         }
     }
     Ok(files_with_sizes)
 }
 
-type DirsWithSizes = HashMap<PathBuf, u32>;
-
-fn get_dir_sizes(files_with_sizes: &FilesWithSizes) -> DirsWithSizes {
-    files_with_sizes
-        .iter()
-        .flat_map(|(file_path, file_size)| {
-            file_path
-                .ancestors()
-                .skip(1)
-                .map(|ancestor_path| (ancestor_path.to_path_buf(), *file_size))
-        })
-        .into_grouping_map()
-        .sum()
+type DirsWithSizes = FastMap<PathBuf, u32>;
+
+fn get_dir_sizes(files_with_sizes: &FilesWithSizes) -> aoc2022lib::Result<DirsWithSizes> {
+    let mut dirs_with_sizes = DirsWithSizes::default();
+    for (file_path, file_size) in files_with_sizes {
+        for ancestor_path in file_path.ancestors().skip(1) {
+            let dir_size = dirs_with_sizes
+                .entry(ancestor_path.to_path_buf())
+                .or_default();
+            *dir_size = dir_size.checked_add(*file_size).ok_or(AocError::Overflow)?;
+        }
+    }
+    Ok(dirs_with_sizes)
 }
 
-pub fn p1(file: &str) -> anyhow::Result<u32> {
-    let upper_bound = 100_000u32;
+/// The terminal output without the first prompt
+fn navigations(file: &str) -> aoc2022lib::Result<&str> {
+    file.strip_prefix("$ ")
+        .ok_or_else(|| AocError::missing_input("commands"))
+}
+
+/// The size of every directory, as the terminal output reveals it
+pub struct Filesystem {
+    dirs_with_sizes: DirsWithSizes,
+}
 
-    let navigations = &file[2..];
+pub fn parse(file: &str) -> aoc2022lib::Result<Filesystem> {
+    let navigations = navigations(file)?;
     let files_with_sizes = files_with_sizes(navigations)?;
 
-    let dirs_with_sizes = get_dir_sizes(&files_with_sizes);
+    Ok(Filesystem {
+        dirs_with_sizes: get_dir_sizes(&files_with_sizes)?,
+    })
+}
+
+pub fn part1(filesystem: &Filesystem) -> aoc2022lib::Result<u32> {
+    let upper_bound = 100_000u32;
 
-    Ok(dirs_with_sizes
+    filesystem
+        .dirs_with_sizes
         .values()
-        .filter(|dir_size| **dir_size <= upper_bound)
-        .sum())
+        .copied()
+        .filter(|dir_size| *dir_size <= upper_bound)
+        .checked_sum()
 }
 
-pub fn p2(file: &str) -> anyhow::Result<u32> {
-    let navigations = &file[2..];
-    let files_with_sizes = files_with_sizes(navigations)?;
-
-    let dirs_with_sizes = get_dir_sizes(&files_with_sizes);
+pub fn part2(filesystem: &Filesystem) -> aoc2022lib::Result<u32> {
+    let dirs_with_sizes = &filesystem.dirs_with_sizes;
 
     let total_space = 70_000_000u32;
-    let total_used_space = *dirs_with_sizes.get(&PathBuf::from("/")).unwrap();
-    let total_available_space = total_space - total_used_space;
+    let total_used_space = *dirs_with_sizes
+        .get(&PathBuf::from("/"))
+        .ok_or_else(|| AocError::missing_input("files in `/`"))?;
+    let total_available_space = total_space
+        .checked_sub(total_used_space)
+        .ok_or_else(|| AocError::invalid_input("more space used than there is"))?;
     let total_to_free_up = 30_000_000u32;
-    let left_to_free_up = total_to_free_up - total_available_space;
+    let left_to_free_up = total_to_free_up.saturating_sub(total_available_space);
 
-    Ok(*dirs_with_sizes
+    dirs_with_sizes
         .values()
         .filter(|dir_size| **dir_size >= left_to_free_up)
         .sorted_unstable()
         .next()
-        .context("At least one directory")?)
+        .copied()
+        .ok_or(AocError::NoSolution)
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use aoc2022lib::{parse::ParseError, AocError};
 use itertools::Itertools;
 
 /// Returns the transposed copy of a collection
@@ -20,6 +21,7 @@ impl<T> TransposeOutOfPlace for Vec<Vec<T>> {
     }
 }
 
+#[derive(Clone)]
 struct Tree {
     height: u32,
     scenicity: usize,
@@ -40,15 +42,34 @@ impl Tree {
     }
 }
 
-fn forest(file: &str) -> Vec<Vec<Tree>> {
-    file.lines()
-        .map(|line| {
-            line.chars()
-                .map(|char| char.to_digit(10).unwrap() + 1)
-                .map(Tree::with_height)
+/// The trees, row by row
+pub struct Forest(Vec<Vec<Tree>>);
+
+// 30373
+pub fn parse(file: &str) -> aoc2022lib::Result<Forest> {
+    let forest: Vec<Vec<Tree>> = file
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            line.char_indices()
+                .map(|(column, char)| {
+                    char.to_digit(10).ok_or_else(|| {
+                        ParseError::at(line, &line[column..], "not a tree height").on_line(idx + 1)
+                    })
+                })
+                .map_ok(|height| Tree::with_height(height + 1))
                 .collect()
         })
-        .collect()
+        .collect::<Result<_, _>>()?;
+
+    let width = forest.first().map_or(0, Vec::len);
+    if width == 0 {
+        return Err(AocError::missing_input("trees"));
+    }
+    if forest.iter().any(|row| row.len() != width) {
+        return Err(AocError::invalid_input("the forest isn't rectangular"));
+    }
+    Ok(Forest(forest))
 }
 
 /// Less exact than [`check_scenicities_in_a_line`] - checks whether
@@ -56,7 +77,9 @@ fn forest(file: &str) -> Vec<Vec<Tree>> {
 fn check_visibilities_in_a_line(line: &mut [Tree]) {
     // save the highest tree of the line so that we
     // don't check past it coming from both directions
-    let position_highest_tree = line.iter().position_max_by_key(|tree| tree.height).unwrap();
+    let Some(position_highest_tree) = line.iter().position_max_by_key(|tree| tree.height) else {
+        return;
+    };
 
     // check the line forwards until the highest tree
     let mut current_max_height = u32::MIN;
@@ -78,9 +101,9 @@ fn check_visibilities_in_a_line(line: &mut [Tree]) {
     }
 }
 
-pub fn p1(file: &str) -> usize {
-    // create the map
-    let mut forest: Vec<Vec<Tree>> = forest(file);
+pub fn part1(Forest(forest): &Forest) -> aoc2022lib::Result<usize> {
+    // copy the map to mark the trees on
+    let mut forest = forest.clone();
 
     // analyze visibility horizontally
     for row in &mut forest {
@@ -95,11 +118,11 @@ pub fn p1(file: &str) -> usize {
         check_visibilities_in_a_line(col);
     }
 
-    forest
+    Ok(forest
         .into_iter()
         .flatten()
         .filter(Tree::is_visible)
-        .count()
+        .count())
 }
 
 /// More exact than [`check_visibilities_in_a_line`] - gets the exact scenicity values
@@ -132,8 +155,8 @@ fn check_scenicities_in_a_line(line: &mut [Tree]) {
         .for_each(|(tree, scenicity)| tree.scenicity *= scenicity);
 }
 
-pub fn p2(file: &str) -> usize {
-    let mut forest: Vec<Vec<Tree>> = forest(file);
+pub fn part2(Forest(forest): &Forest) -> aoc2022lib::Result<usize> {
+    let mut forest = forest.clone();
 
     for row in &mut forest {
         check_scenicities_in_a_line(row);
@@ -150,7 +173,15 @@ pub fn p2(file: &str) -> usize {
         .flatten()
         .map(|tree| tree.scenicity)
         .max()
-        .unwrap()
+        .ok_or(AocError::NoSolution)
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
@@ -161,21 +192,21 @@ mod tests {
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
-        assert_eq!(p1(&inp), 21);
+        assert_eq!(p1(&inp).unwrap(), 21);
     }
     #[test]
     fn real_p1() {
         let inp = read_to_string("inputs/real.txt").unwrap();
-        assert_eq!(p1(&inp), 1708);
+        assert_eq!(p1(&inp).unwrap(), 1708);
     }
     #[test]
     fn test_p2() {
         let inp = read_to_string("inputs/test.txt").unwrap();
-        assert_eq!(p2(&inp), 8);
+        assert_eq!(p2(&inp).unwrap(), 8);
     }
     #[test]
     fn real_p2() {
         let inp = read_to_string("inputs/real.txt").unwrap();
-        assert_eq!(p2(&inp), 504_000);
+        assert_eq!(p2(&inp).unwrap(), 504_000);
     }
 }
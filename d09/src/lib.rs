@@ -1,8 +1,12 @@
-use std::{collections::HashSet, iter, str::FromStr};
+use std::{convert::Infallible, io::BufRead, iter, str::FromStr};
 
-use derive_deref::{Deref, DerefMut};
-
-use aoc2022lib::points::Point2D;
+use aoc2022lib::{
+    parse::ParseError,
+    points::Point2D,
+    simulation::{Driver, Simulation},
+    stream::for_each_line,
+    SparseGrid,
+};
 
 type Point = Point2D<i32>;
 
@@ -12,17 +16,10 @@ enum Direction2D {
     Down,
     Left,
     Right,
-    UpRight,
-    UpLeft,
-    DownRight,
-    DownLeft,
 }
 
-#[derive(Debug)]
-struct ParseDirectionError;
-
 impl FromStr for Direction2D {
-    type Err = ParseDirectionError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -30,7 +27,18 @@ impl FromStr for Direction2D {
             "D" => Ok(Direction2D::Down),
             "L" => Ok(Direction2D::Left),
             "R" => Ok(Direction2D::Right),
-            _ => Err(ParseDirectionError),
+            _ => Err(ParseError::new(s, "not a direction")),
+        }
+    }
+}
+
+impl From<Direction2D> for Point {
+    fn from(direction: Direction2D) -> Self {
+        match direction {
+            Direction2D::Up => Point2D(0, 1),
+            Direction2D::Down => Point2D(0, -1),
+            Direction2D::Left => Point2D(-1, 0),
+            Direction2D::Right => Point2D(1, 0),
         }
     }
 }
@@ -41,51 +49,96 @@ trait Move2D {
 
 impl Move2D for Point {
     fn r#move(&mut self, direction: Direction2D) {
-        use Direction2D as D;
-        match direction {
-            D::Up => self.1 += 1,
-            D::Down => self.1 -= 1,
-            D::Left => self.0 -= 1,
-            D::Right => self.0 += 1,
-            D::UpRight => {
-                self.r#move(D::Up);
-                self.r#move(D::Right);
-            }
-            D::UpLeft => {
-                self.r#move(D::Up);
-                self.r#move(D::Left);
-            }
-            D::DownRight => {
-                self.r#move(D::Down);
-                self.r#move(D::Right);
-            }
-            D::DownLeft => {
-                self.r#move(D::Down);
-                self.r#move(D::Left);
-            }
+        *self += direction.into();
+    }
+}
+
+// R 4
+fn head_move(r#move: &str) -> Result<(Direction2D, usize), ParseError> {
+    let (direction, num_repeats) = r#move
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(r#move, "not a direction and a distance"))?;
+    let direction = direction.parse::<Direction2D>()?;
+    let num_repeats = num_repeats
+        .parse::<usize>()
+        .map_err(|err| ParseError::at(r#move, num_repeats, err))?;
+    Ok((direction, num_repeats))
+}
+
+/// Where the head goes, each move a direction and how many points that way
+pub struct HeadMoves(Vec<(Direction2D, usize)>);
+
+pub fn parse(file: &str) -> aoc2022lib::Result<HeadMoves> {
+    let head_moves = file
+        .lines()
+        .enumerate()
+        .map(|(idx, r#move)| head_move(r#move).map_err(|err| err.on_line(idx + 1)))
+        .collect::<Result<_, _>>()?;
+    Ok(HeadMoves(head_moves))
+}
+
+/// The head of the rope moving a single point per step, the rest of it following
+struct RopeSimulation<'a> {
+    rope: Rope,
+    head_moves: &'a [(Direction2D, usize)],
+    /// The move the head is in the middle of
+    move_idx: usize,
+    /// How many points the head has gone in the current move so far
+    num_moved: usize,
+    visited_by_tail: SparseGrid<()>,
+}
+
+impl<'a> RopeSimulation<'a> {
+    fn new(len: usize, HeadMoves(head_moves): &'a HeadMoves) -> Self {
+        let mut simulation = Self {
+            rope: Rope::with_length(len),
+            head_moves,
+            move_idx: 0,
+            num_moved: 0,
+            visited_by_tail: iter::once(Point2D(0, 0)).collect(),
         };
+        simulation.skip_finished_moves();
+        simulation
+    }
+
+    /// Goes on to the next move the head isn't done with, if there is one
+    fn skip_finished_moves(&mut self) {
+        while self
+            .head_moves
+            .get(self.move_idx)
+            .is_some_and(|&(_, distance)| self.num_moved >= distance)
+        {
+            self.move_idx += 1;
+            self.num_moved = 0;
+        }
     }
 }
 
-#[derive(Deref, DerefMut)]
-struct Moves(Vec<Direction2D>);
+impl Simulation for RopeSimulation<'_> {
+    type Snapshot = Rope;
+    type Error = Infallible;
 
-pub fn p1(file: &str) -> usize {
-    let mut rope = Rope::with_length(2);
-    file.lines()
-        .flat_map(|r#move| {
-            let (direction, num_repeats) = r#move.split_once(' ').unwrap();
-            let direction = direction.parse::<Direction2D>().unwrap();
-            let num_repeats = num_repeats.parse::<usize>().unwrap();
-            iter::repeat(direction).take(num_repeats)
-        })
-        .map(|direction| {
-            rope.r#move(direction);
-            *rope.last().unwrap()
-        })
-        .chain(iter::once(Point2D(0, 0)))
-        .collect::<HashSet<_>>()
-        .len()
+    fn step(&mut self) -> Result<(), Infallible> {
+        let (direction, _) = self.head_moves[self.move_idx];
+        self.rope.r#move(direction);
+        self.num_moved += 1;
+        self.skip_finished_moves();
+        self.visited_by_tail.insert(*self.rope.last().unwrap(), ());
+        Ok(())
+    }
+
+    fn is_done(&self) -> bool {
+        self.move_idx == self.head_moves.len()
+    }
+
+    fn snapshot(&self) -> Rope {
+        self.rope.clone()
+    }
+}
+
+pub fn part1(head_moves: &HeadMoves) -> aoc2022lib::Result<usize> {
+    let rope = RopeSimulation::new(2, head_moves);
+    Ok(Driver::new(rope).run()?.visited_by_tail.len())
 }
 
 type Rope = Vec<Point>;
@@ -110,60 +163,69 @@ impl Move2D for Rope {
         let mut prev = *head;
 
         for curr in self.iter_mut().skip(1) {
-            use Direction2D as D;
-            let move_to_catch_up = match (prev.0 - curr.0, prev.1 - curr.1) {
-                // knots touch - no catching-up necessary
-                (-1..=1, -1..=1) => None,
-                // catch-up diagonally
-                (1 | 2, 1 | 2) => Some(D::UpRight),
-                (-1 | -2, 1 | 2) => Some(D::UpLeft),
-                (1 | 2, -1 | -2) => Some(D::DownRight),
-                (-1 | -2, -1 | -2) => Some(D::DownLeft),
-                // catch-up vertically/horizontally
-                (0, 2) => Some(D::Up),
-                (0, -2) => Some(D::Down),
-                (2, 0) => Some(D::Right),
-                (-2, 0) => Some(D::Left),
-                _ => unreachable!(),
-            };
-
-            match move_to_catch_up {
-                // check the distance to the previous knot
-                // - if too big, teleport to it
-                Some(direction) => curr.r#move(direction),
-                // - otherwise:
-                // 1. don't move at all
-                // 2. observe that all the necessary pulls have already been made and
-                //    the rest of the rope doesn't need to move, so don't check further
-                None => break,
+            let distance = prev - *curr;
+
+            // check the distance to the previous knot
+            // - if the knots touch:
+            // 1. don't move at all
+            // 2. observe that all the necessary pulls have already been made and
+            //    the rest of the rope doesn't need to move, so don't check further
+            if distance.chebyshev(Point2D::default()) <= 1 {
+                break;
             }
+            // - otherwise catch up by a step on each axis the previous knot is away on
+            *curr += distance.signum();
+
             prev = *curr;
         }
     }
 }
 
-pub fn p2(file: &str) -> usize {
-    let mut rope = Rope::with_length(10);
-    file.lines()
-        .flat_map(|r#move| {
-            let (direction, num_repeats) = r#move.split_once(' ').unwrap();
-            let direction = direction.parse::<Direction2D>().unwrap();
-            let num_repeats = num_repeats.parse::<usize>().unwrap();
-            iter::repeat(direction).take(num_repeats)
-        })
-        .map(|direction| {
+pub fn part2(head_moves: &HeadMoves) -> aoc2022lib::Result<usize> {
+    let rope = RopeSimulation::new(10, head_moves);
+    Ok(Driver::new(rope).run()?.visited_by_tail.len())
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?)
+}
+
+/// Where the tail of a `len` long rope goes, reading the moves a line at a time
+fn tail_visits_from_reader<R: BufRead>(reader: R, len: usize) -> aoc2022lib::Result<usize> {
+    let mut rope = Rope::with_length(len);
+    let mut visited_by_tail: SparseGrid<()> = iter::once(Point2D(0, 0)).collect();
+    for_each_line(reader, |line_num, line| {
+        let (direction, num_repeats) = head_move(line).map_err(|err| err.on_line(line_num))?;
+        for _ in 0..num_repeats {
             rope.r#move(direction);
-            *rope.last().unwrap()
-        })
-        .chain(iter::once(Point2D(0, 0)))
-        .collect::<HashSet<_>>()
-        .len()
+            visited_by_tail.insert(*rope.last().unwrap(), ());
+        }
+        Ok(())
+    })?;
+    Ok(visited_by_tail.len())
+}
+
+/// How many positions the tail of a 2 knot rope visits, reading the moves a line at a time
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    tail_visits_from_reader(reader, 2)
+}
+
+/// How many positions the tail of a 10 knot rope visits, reading the moves a line at a time
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<usize> {
+    tail_visits_from_reader(reader, 10)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::read_to_string;
+    use std::{
+        fs::{read_to_string, File},
+        io::BufReader,
+    };
 
     #[test]
     fn move_2_rope() {
@@ -172,28 +234,48 @@ mod tests {
         assert_eq!(rope, vec![Point2D(0, 1), Point2D(0, 0)]);
     }
     #[test]
+    fn long_moves_stay_lazy() {
+        let head_moves = parse("R 18446744073709551615\nU 0\nL 2").unwrap();
+        let mut driver = Driver::new(RopeSimulation::new(2, &head_moves));
+        assert_eq!(
+            driver.run_to(3).unwrap().rope,
+            vec![Point2D(3, 0), Point2D(2, 0)]
+        );
+        assert_eq!(p1("U 0\nR 3\nD 0").unwrap(), 3);
+    }
+    #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test1.txt").unwrap();
-        assert_eq!(p1(&inp), 13);
+        assert_eq!(p1(&inp).unwrap(), 13);
+    }
+    #[test]
+    fn test_p1_from_reader() {
+        let inp = BufReader::new(File::open("inputs/test1.txt").unwrap());
+        assert_eq!(p1_from_reader(inp).unwrap(), 13);
     }
     #[test]
     fn real_p1() {
         let inp = read_to_string("inputs/real.txt").unwrap();
-        assert_eq!(p1(&inp), 5960);
+        assert_eq!(p1(&inp).unwrap(), 5960);
     }
     #[test]
     fn test1_p2() {
         let inp = read_to_string("inputs/test1.txt").unwrap();
-        assert_eq!(p2(&inp), 1);
+        assert_eq!(p2(&inp).unwrap(), 1);
     }
     #[test]
     fn test2_p2() {
         let inp = read_to_string("inputs/test2.txt").unwrap();
-        assert_eq!(p2(&inp), 36);
+        assert_eq!(p2(&inp).unwrap(), 36);
+    }
+    #[test]
+    fn test2_p2_from_reader() {
+        let inp = BufReader::new(File::open("inputs/test2.txt").unwrap());
+        assert_eq!(p2_from_reader(inp).unwrap(), 36);
     }
     #[test]
     fn real_p2() {
         let inp = read_to_string("inputs/real.txt").unwrap();
-        assert_eq!(p2(&inp), 2327);
+        assert_eq!(p2(&inp).unwrap(), 2327);
     }
 }
@@ -1,10 +1,13 @@
-use aoc2022lib::impl_from_str_from_nom_parser;
+use aoc2022lib::{
+    impl_from_str_from_nom_parser, math::CheckedFold, ocr::decode_pixels, render::Canvas,
+    stream::for_each_line, AocError,
+};
 
 use nom::{
     branch::alt, bytes::complete::tag, character::complete::i32, combinator::map,
     sequence::preceded, IResult,
 };
-use std::{collections::BTreeMap, str::FromStr};
+use std::{collections::BTreeMap, io::BufRead, str::FromStr};
 
 #[derive(Clone, Copy)]
 enum Operation {
@@ -26,24 +29,31 @@ fn operation(input: &str) -> IResult<&str, Operation> {
 
 impl_from_str_from_nom_parser!(operation, Operation);
 
-fn operations(file: &str, init_value: i32) -> BTreeMap<usize, i32> {
-    let mut register_history = BTreeMap::new();
+/// The register's value from each cycle on where it changes
+pub struct RegisterHistory(BTreeMap<usize, i32>);
+
+pub fn parse(file: &str) -> aoc2022lib::Result<RegisterHistory> {
+    operations(file, 1).map(RegisterHistory)
+}
+
+/// The register's value from each cycle on where it changes, starting with `init_value`
+fn operations(file: &str, init_value: i32) -> aoc2022lib::Result<BTreeMap<usize, i32>> {
+    let mut register_history = BTreeMap::from([(0, init_value)]);
     let mut cycle = 0;
     let mut register_value = init_value;
-    for line in file.lines() {
-        match Operation::from_str(line) {
-            Ok(Operation::Addx(num)) => {
+    for (idx, line) in file.lines().enumerate() {
+        match Operation::from_str(line).map_err(|err| err.on_line(idx + 1))? {
+            Operation::Addx(num) => {
                 cycle += 2;
-                register_value += num;
+                register_value = register_value.checked_add(num).ok_or(AocError::Overflow)?;
                 register_history.insert(cycle, register_value);
             }
-            Ok(Operation::Noop) => {
+            Operation::Noop => {
                 cycle += 1;
             }
-            _ => unimplemented!(),
         }
     }
-    register_history
+    Ok(register_history)
 }
 
 trait BiggestPrevious<Q> {
@@ -58,69 +68,164 @@ impl BiggestPrevious<usize> for BTreeMap<usize, i32> {
     }
 }
 
-#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
-pub fn p1(file: &str) -> i32 {
-    let interesting_cycles = (20..=220).step_by(40).collect::<Vec<_>>();
+fn signal_strength(cycle: usize, register_value: i32) -> aoc2022lib::Result<i32> {
+    i32::try_from(cycle)?
+        .checked_mul(register_value)
+        .ok_or(AocError::Overflow)
+}
 
-    let register_history = operations(file, 1);
+pub fn part1(RegisterHistory(register_history): &RegisterHistory) -> aoc2022lib::Result<i32> {
+    let interesting_cycles = (20..=220).step_by(40);
 
     interesting_cycles
+        .map(|cycle| {
+            let register_value = register_history
+                .biggest_previous(cycle - 1)
+                .copied()
+                .unwrap_or_default();
+            signal_strength(cycle, register_value)
+        })
+        .collect::<aoc2022lib::Result<Vec<_>>>()?
         .into_iter()
-        .map(|cycle| cycle as i32 * register_history.biggest_previous(cycle - 1).unwrap())
-        .sum()
+        .checked_sum()
 }
 
+/// What the CRT draws, a lit pixel for each cycle the sprite is under the beam
 #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
-pub fn p2(file: &str) -> String {
-    struct Crt {
-        width: usize,
-        height: usize,
+pub fn screen(RegisterHistory(register_history): &RegisterHistory) -> Canvas<bool> {
+    let mut screen = Canvas::new(40, 6, false);
+
+    for row_num in 0..screen.height() {
+        for col_num in 0..screen.width() {
+            let cycle = screen.width() * row_num + col_num;
+
+            // only check against the horizontal position of the sprite
+            let crt_position = col_num;
+
+            let center_of_sprite = register_history
+                .biggest_previous(cycle)
+                .copied()
+                .unwrap_or_default();
+
+            if center_of_sprite.abs_diff(crt_position as i32) <= 1 {
+                screen.set(col_num, row_num, true);
+            }
+        }
     }
+    screen
+}
 
-    let crt = Crt {
-        width: 40,
-        height: 6,
-    };
+pub fn part2(register_history: &RegisterHistory) -> aoc2022lib::Result<String> {
+    Ok(screen(register_history).to_string())
+}
+
+/// The letters [`part2`] draws on the screen
+pub fn part2_decoded(register_history: &RegisterHistory) -> aoc2022lib::Result<String> {
+    let screen = screen(register_history);
+    Ok(decode_pixels(&screen.rows().collect::<Vec<_>>())?)
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<i32> {
+    part1(&parse(file)?)
+}
 
-    let register_history = operations(file, 1);
+pub fn p2(file: &str) -> aoc2022lib::Result<String> {
+    part2(&parse(file)?)
+}
 
-    let rows: Vec<String> = (0..crt.height)
-        .map(|row_num| {
-            (0..crt.width)
-                .map(|col_num| {
-                    let cycle = crt.width * row_num + col_num;
+pub fn p2_decoded(file: &str) -> aoc2022lib::Result<String> {
+    part2_decoded(&parse(file)?)
+}
 
-                    // only check against the horizontal position of the sprite
-                    let crt_position = col_num;
+/// Calls `on_cycle` with the number of each of the first `num_cycles` cycles, counting from 1,
+/// and the register's value during it, reading the operations a line at a time
+fn for_each_cycle<R: BufRead>(
+    reader: R,
+    num_cycles: usize,
+    mut on_cycle: impl FnMut(usize, i32) -> aoc2022lib::Result<()>,
+) -> aoc2022lib::Result<()> {
+    let mut cycle = 0;
+    let mut register_value = 1;
+    for_each_line(reader, |line_num, line| {
+        let (num_cycles_taken, added) =
+            match Operation::from_str(line).map_err(|err| err.on_line(line_num))? {
+                Operation::Addx(num) => (2, num),
+                Operation::Noop => (1, 0),
+            };
+        for _ in 0..num_cycles_taken {
+            cycle += 1;
+            if cycle <= num_cycles {
+                on_cycle(cycle, register_value)?;
+            }
+        }
+        register_value = register_value
+            .checked_add(added)
+            .ok_or(AocError::Overflow)?;
+        Ok(())
+    })?;
+    // the register keeps its value once the program's done
+    for cycle in cycle + 1..=num_cycles {
+        on_cycle(cycle, register_value)?;
+    }
+    Ok(())
+}
 
-                    let center_of_sprite = register_history.biggest_previous(cycle).unwrap();
+/// The sum of the signal strengths every 40 cycles from the 20th to the 220th, reading the
+/// program a line at a time
+pub fn p1_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<i32> {
+    let mut sum = 0i32;
+    for_each_cycle(reader, 220, |cycle, register_value| {
+        if cycle % 40 == 20 {
+            sum = sum
+                .checked_add(signal_strength(cycle, register_value)?)
+                .ok_or(AocError::Overflow)?;
+        }
+        Ok(())
+    })?;
+    Ok(sum)
+}
 
-                    if center_of_sprite.abs_diff(crt_position as i32) <= 1 {
-                        '#'
-                    } else {
-                        '.'
-                    }
-                })
-                .collect::<String>()
-        })
-        .collect();
-    rows.join("\n")
+/// What the CRT draws, reading the program a line at a time
+#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+pub fn p2_from_reader<R: BufRead>(reader: R) -> aoc2022lib::Result<String> {
+    let mut screen = Canvas::new(40, 6, false);
+    let width = screen.width();
+    for_each_cycle(
+        reader,
+        width * screen.height(),
+        |cycle, center_of_sprite| {
+            let (row_num, col_num) = ((cycle - 1) / width, (cycle - 1) % width);
+            if center_of_sprite.abs_diff(col_num as i32) <= 1 {
+                screen.set(col_num, row_num, true);
+            }
+            Ok(())
+        },
+    )?;
+    Ok(screen.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::read_to_string;
+    use std::{
+        fs::{read_to_string, File},
+        io::BufReader,
+    };
 
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
-        assert_eq!(p1(&inp), 13140);
+        assert_eq!(p1(&inp).unwrap(), 13140);
+    }
+    #[test]
+    fn test_p1_from_reader() {
+        let inp = BufReader::new(File::open("inputs/test.txt").unwrap());
+        assert_eq!(p1_from_reader(inp).unwrap(), 13140);
     }
     #[test]
     fn real_p1() {
         let inp = read_to_string("inputs/real.txt").unwrap();
-        assert_eq!(p1(&inp), 15360);
+        assert_eq!(p1(&inp).unwrap(), 15360);
     }
     #[test]
     fn test_p2() {
@@ -131,7 +236,12 @@ mod tests {
 #####.....#####.....#####.....#####.....
 ######......######......######......####
 #######.......#######.......#######.....";
-        assert_eq!(p2(&inp), out);
+        assert_eq!(p2(&inp).unwrap(), out);
+    }
+    #[test]
+    fn small_p2_from_reader() {
+        let inp = "noop\naddx 3\naddx -5\n";
+        assert_eq!(p2_from_reader(inp.as_bytes()).unwrap(), p2(inp).unwrap());
     }
     #[test]
     fn real_p2() {
@@ -142,6 +252,11 @@ mod tests {
 ###..#..#.#....#..#....#.#.##..#...####.
 #....#..#.#....#..#.#..#.#..#.#....#..#.
 #....#..#.####.#..#..##...###.####.#..#.";
-        assert_eq!(p2(&inp), out);
+        assert_eq!(p2(&inp).unwrap(), out);
+    }
+    #[test]
+    fn real_p2_decoded() {
+        let inp = read_to_string("inputs/real.txt").unwrap();
+        assert_eq!(p2_decoded(&inp).unwrap(), "PHLHJGZA");
     }
 }
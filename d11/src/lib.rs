@@ -1,8 +1,11 @@
-use std::ops::{Add, Mul};
-use std::str::FromStr;
+use std::{iter, str::FromStr};
 
-use anyhow::Context;
-use aoc2022lib::parse::n;
+use aoc2022lib::{
+    math::{lcm_all, CheckedFold, ModInt},
+    parse::{finish, n, ParseError},
+    simulation::{Driver, Simulation},
+    AocError,
+};
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -11,19 +14,37 @@ use nom::{
     combinator::{map, map_res, value},
     multi::separated_list0,
     sequence::{delimited, preceded, separated_pair, tuple},
-    Finish, IResult,
+    IResult,
 };
-use num::Integer;
 
 #[allow(clippy::struct_field_names)]
+#[derive(Clone)]
 struct Monkey<N: Copy> {
     inventory: Vec<N>,
     operation: Operation<N>,
-    divisible_by: N,
+    divisible_by: u64,
     monkey_true: usize,
     monkey_false: usize,
 }
 
+impl<N: Copy> Monkey<N> {
+    /// The same monkey, keeping track of worry levels as `M`
+    fn map_worry<M: Copy>(self, f: impl Fn(N) -> M) -> Monkey<M> {
+        let Operation(operator, operand) = self.operation;
+        let operand = match operand {
+            Operand::Old => Operand::Old,
+            Operand::Number(value) => Operand::Number(f(value)),
+        };
+        Monkey {
+            inventory: self.inventory.into_iter().map(&f).collect(),
+            operation: Operation(operator, operand),
+            divisible_by: self.divisible_by,
+            monkey_true: self.monkey_true,
+            monkey_false: self.monkey_false,
+        }
+    }
+}
+
 // 79, 98
 fn starting_items<N: FromStr>(input: &str) -> IResult<&str, Vec<N>> {
     separated_list0(tag(", "), n)(input)
@@ -66,7 +87,7 @@ fn operation<N: FromStr + Copy>(input: &str) -> IResult<&str, Operation<N>> {
     )(input)
 }
 
-fn divisible_by<N: FromStr + Copy>(input: &str) -> IResult<&str, N> {
+fn divisible_by(input: &str) -> IResult<&str, u64> {
     preceded(tag("divisible by "), n)(input)
 }
 
@@ -90,7 +111,7 @@ fn monkey<N: FromStr + Copy>(input: &str) -> IResult<&str, Monkey<N>> {
             delimited(tag("Monkey "), digit1, tag(":")),
             preceded(tag("\n  Starting items: "), starting_items::<N>),
             preceded(tag("\n  Operation: "), operation::<N>),
-            preceded(tag("\n  Test: "), divisible_by::<N>),
+            preceded(tag("\n  Test: "), divisible_by),
             preceded(tag("\n    If true: "), throw_to),
             preceded(tag("\n    If false: "), throw_to),
         )),
@@ -109,124 +130,210 @@ where
     N: FromStr + Copy,
     <N as FromStr>::Err: std::error::Error + Send + Sync + 'static,
 {
-    type Err = nom::error::Error<String>;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match monkey(s).finish() {
-            Ok((_remaining, monkey)) => Ok(monkey),
-            Err(nom::error::Error { input, code }) => Err(Self::Err {
-                input: input.to_string(),
-                code,
-            }),
-        }
+        finish(s, monkey(s))
     }
 }
 
-trait ApplyOperation {
-    fn apply_operation(self, operation: Operation<Self>) -> Self
-    where
-        Self: Sized + Copy;
-}
-
-impl<N> ApplyOperation for N
+// Monkey 0:
+// ...
+//
+// Monkey 1:
+// ...
+fn monkeys<N>(file: &str) -> aoc2022lib::Result<Vec<Monkey<N>>>
 where
-    N: Add<Output = Self> + Mul<Output = Self> + Copy,
+    N: FromStr + Copy,
+    <N as FromStr>::Err: std::error::Error + Send + Sync + 'static,
 {
-    fn apply_operation(self, operation: Operation<Self>) -> Self {
+    let mut line = 1;
+    let mut monkeys = Vec::new();
+    for monkey in file.split("\n\n") {
+        monkeys.push(Monkey::from_str(monkey).map_err(|err| err.on_line(line))?);
+        line += monkey.lines().count() + 1;
+    }
+    if let Some(monkey) = monkeys
+        .iter()
+        .find(|monkey| monkey.monkey_true.max(monkey.monkey_false) >= monkeys.len())
+    {
+        return Err(AocError::invalid_input(format!(
+            "there's no monkey {}",
+            monkey.monkey_true.max(monkey.monkey_false)
+        )));
+    }
+    Ok(monkeys)
+}
+
+trait WorryLevel: Copy + Sized {
+    /// `None` if the worry level gets too big to keep track of
+    fn apply_operation(self, operation: Operation<Self>) -> Option<Self>;
+
+    fn divisible_by(self, divisor: u64) -> bool;
+}
+
+impl WorryLevel for u64 {
+    fn apply_operation(self, operation: Operation<Self>) -> Option<Self> {
         match operation {
+            Operation(Operator::Add, Operand::Number(value)) => self.checked_add(value),
+            Operation(Operator::Mul, Operand::Number(value)) => self.checked_mul(value),
+            Operation(Operator::Add, Operand::Old) => self.checked_add(self),
+            Operation(Operator::Mul, Operand::Old) => self.checked_mul(self),
+        }
+    }
+
+    fn divisible_by(self, divisor: u64) -> bool {
+        self.is_multiple_of(divisor)
+    }
+}
+
+impl WorryLevel for ModInt {
+    fn apply_operation(self, operation: Operation<Self>) -> Option<Self> {
+        Some(match operation {
             Operation(Operator::Add, Operand::Number(value)) => self + value,
             Operation(Operator::Mul, Operand::Number(value)) => self * value,
             Operation(Operator::Add, Operand::Old) => self + self,
             Operation(Operator::Mul, Operand::Old) => self * self,
+        })
+    }
+
+    /// Only tells if `divisor` divides the modulus
+    fn divisible_by(self, divisor: u64) -> bool {
+        self.value().is_multiple_of(divisor)
+    }
+}
+
+/// The monkeys throwing items around, a round per step
+struct KeepAway<N: Copy> {
+    monkeys: Vec<Monkey<N>>,
+    /// How much your worry level decreases after each inspection
+    relief: fn(N) -> N,
+    activities: Vec<usize>,
+    inventories_to_transfer: Vec<Vec<N>>,
+    num_rounds_played: u32,
+    num_rounds: u32,
+}
+
+impl<N: Copy> KeepAway<N> {
+    fn new(monkeys: Vec<Monkey<N>>, relief: fn(N) -> N, num_rounds: u32) -> Self {
+        Self {
+            relief,
+            activities: vec![0; monkeys.len()],
+            inventories_to_transfer: vec![Vec::new(); monkeys.len()],
+            monkeys,
+            num_rounds_played: 0,
+            num_rounds,
         }
     }
+
+    fn monkey_business(&self) -> aoc2022lib::Result<usize> {
+        self.activities
+            .iter()
+            .copied()
+            .sorted_unstable()
+            .rev()
+            .take(2)
+            .checked_product()
+    }
 }
 
-pub fn p1(file: &str, num_rounds: u32) -> anyhow::Result<usize> {
-    let mut monkeys = file
-        .split("\n\n")
-        .map(Monkey::<u32>::from_str)
-        .collect::<Result<Vec<_>, _>>()?;
+impl<N: WorryLevel> Simulation for KeepAway<N> {
+    /// The items each monkey holds
+    type Snapshot = Vec<Vec<N>>;
+    /// The worry levels got too big to keep track of
+    type Error = AocError;
 
-    let mut activities: Vec<usize> = vec![0; monkeys.len()];
-    let mut inventories_to_transfer = vec![Vec::new(); monkeys.len()];
-    for _ in 0..num_rounds {
-        for (idx, monkey) in monkeys.iter_mut().enumerate() {
-            monkey.inventory.append(&mut inventories_to_transfer[idx]);
+    fn step(&mut self) -> aoc2022lib::Result<()> {
+        for (idx, monkey) in self.monkeys.iter_mut().enumerate() {
+            monkey
+                .inventory
+                .append(&mut self.inventories_to_transfer[idx]);
 
             if monkey.inventory.is_empty() {
                 continue;
             }
 
-            activities[idx] += monkey.inventory.len();
+            self.activities[idx] += monkey.inventory.len();
 
-            let (items_monkey_true, items_monkey_false): (Vec<u32>, Vec<u32>) = monkey
+            let item_worries = monkey
                 .inventory
                 .drain(..)
                 // monkey applies its operation
                 .map(|item_worry| item_worry.apply_operation(monkey.operation))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(AocError::Overflow)?;
+            let (items_monkey_true, items_monkey_false): (Vec<N>, Vec<N>) = item_worries
+                .into_iter()
                 // your worry level decreases
-                .map(|item_worry| item_worry / 3)
+                .map(self.relief)
                 // monkey inspects each item
-                .partition(|item_worry| item_worry.is_multiple_of(&monkey.divisible_by));
+                .partition(|item_worry| item_worry.divisible_by(monkey.divisible_by));
 
-            inventories_to_transfer[monkey.monkey_true].extend(items_monkey_true);
-            inventories_to_transfer[monkey.monkey_false].extend(items_monkey_false);
+            self.inventories_to_transfer[monkey.monkey_true].extend(items_monkey_true);
+            self.inventories_to_transfer[monkey.monkey_false].extend(items_monkey_false);
         }
+        self.num_rounds_played += 1;
+        Ok(())
+    }
+
+    fn is_done(&self) -> bool {
+        self.num_rounds_played == self.num_rounds
     }
 
-    Ok(activities
-        .into_iter()
-        .sorted_unstable()
-        .rev()
-        .take(2)
-        .product())
+    fn snapshot(&self) -> Vec<Vec<N>> {
+        self.monkeys
+            .iter()
+            .zip(&self.inventories_to_transfer)
+            .map(|(monkey, to_transfer)| {
+                monkey
+                    .inventory
+                    .iter()
+                    .chain(to_transfer)
+                    .copied()
+                    .collect()
+            })
+            .collect()
+    }
 }
 
-pub fn p2(file: &str, num_rounds: u32) -> anyhow::Result<usize> {
-    let mut monkeys = file
-        .split("\n\n")
-        .map(Monkey::<u64>::from_str)
-        .collect::<Result<Vec<_>, _>>()?;
+/// The monkeys as they are before the first round
+pub struct Monkeys(Vec<Monkey<u64>>);
 
-    let divisibility_tests_lcm = monkeys
-        .iter()
-        .map(|monkey| monkey.divisible_by)
-        .reduce(|acc, e| acc.lcm(&e))
-        .context("non-zero amount of monkeys")?;
+pub fn parse(file: &str) -> aoc2022lib::Result<Monkeys> {
+    monkeys(file).map(Monkeys)
+}
 
-    let mut activities: Vec<usize> = vec![0; monkeys.len()];
-    let mut inventories_to_transfer = vec![Vec::new(); monkeys.len()];
-    for _ in 0..num_rounds {
-        for (idx, monkey) in monkeys.iter_mut().enumerate() {
-            monkey.inventory.append(&mut inventories_to_transfer[idx]);
+pub fn part1(Monkeys(monkeys): &Monkeys, num_rounds: u32) -> aoc2022lib::Result<usize> {
+    let monkeys = monkeys.clone();
 
-            if monkey.inventory.is_empty() {
-                continue;
-            }
+    let keep_away = KeepAway::new(monkeys, |item_worry| item_worry / 3, num_rounds);
+    Driver::new(keep_away).run()?.monkey_business()
+}
 
-            activities[idx] += monkey.inventory.len();
+pub fn part2(Monkeys(monkeys): &Monkeys, num_rounds: u32) -> aoc2022lib::Result<usize> {
+    // worry levels only matter as far as the divisibility tests can tell them apart
+    if monkeys.iter().any(|monkey| monkey.divisible_by == 0) {
+        return Err(AocError::invalid_input("nothing is divisible by 0"));
+    }
+    let modulus = lcm_all(iter::once(1).chain(monkeys.iter().map(|monkey| monkey.divisible_by)))
+        .ok_or(AocError::Overflow)?;
+    let monkeys = monkeys
+        .iter()
+        .cloned()
+        .map(|monkey| monkey.map_worry(|worry| ModInt::new(worry, modulus)))
+        .collect_vec();
 
-            let (items_monkey_true, items_monkey_false): (Vec<u64>, Vec<u64>) = monkey
-                .inventory
-                .drain(..)
-                // monkey applies its operation
-                .map(|item_worry| item_worry.apply_operation(monkey.operation))
-                .map(|item_worry| item_worry % divisibility_tests_lcm)
-                // monkey inspects each item
-                .partition(|item_worry| item_worry.is_multiple_of(&monkey.divisible_by));
+    // worry levels don't decrease any more
+    let keep_away = KeepAway::new(monkeys, |item_worry| item_worry, num_rounds);
+    Driver::new(keep_away).run()?.monkey_business()
+}
 
-            inventories_to_transfer[monkey.monkey_true].extend(items_monkey_true);
-            inventories_to_transfer[monkey.monkey_false].extend(items_monkey_false);
-        }
-    }
+pub fn p1(file: &str, num_rounds: u32) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?, num_rounds)
+}
 
-    Ok(activities
-        .into_iter()
-        .sorted_unstable()
-        .rev()
-        .take(2)
-        .product())
+pub fn p2(file: &str, num_rounds: u32) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?, num_rounds)
 }
 
 #[cfg(test)]
@@ -234,6 +341,13 @@ mod tests {
     use super::*;
     use std::fs::read_to_string;
 
+    #[test]
+    fn divisible_modulo_lcm() {
+        // worry levels kept modulo the lcm of 3 and 5
+        assert!(ModInt::new(20, 15).divisible_by(5));
+        assert!(ModInt::new(30, 15).divisible_by(3));
+        assert!(!ModInt::new(22, 15).divisible_by(3));
+    }
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
@@ -1,45 +1,45 @@
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
-use anyhow::Context;
-use aoc2022lib::points::Point2D;
-use pathfinding::directed::astar;
+use aoc2022lib::{
+    collections::FastMap,
+    graph::{bfs, Graph},
+    parse::ParseError,
+    points::Point2D,
+    AocError,
+};
 
-struct HeightMap<T> {
+pub struct HeightMap<T> {
     start: Point2D<T>,
     goal: Point2D<T>,
     num_rows: usize,
     num_cols: usize,
-    heights: HashMap<Point2D<T>, u32>,
+    heights: FastMap<Point2D<T>, u32>,
 }
 
 impl HeightMap<usize> {
-    fn climbable_neighbours(&self, point: Point2D<usize>) -> Vec<Point2D<usize>> {
-        let Point2D(x, y) = point;
+    fn climbable_neighbours(
+        &self,
+        point: Point2D<usize>,
+    ) -> impl Iterator<Item = Point2D<usize>> + '_ {
         let this_height = self.heights[&point];
 
-        let mut potential_neighbours = Vec::new();
-        if x > 0 {
-            potential_neighbours.push(Point2D(x - 1, y));
-        }
-        if x < self.num_cols - 1 {
-            potential_neighbours.push(Point2D(x + 1, y));
-        }
-        if y > 0 {
-            potential_neighbours.push(Point2D(x, y - 1));
-        }
-        if y < self.num_rows - 1 {
-            potential_neighbours.push(Point2D(x, y + 1));
-        }
+        point
+            .checked_neighbours()
+            .filter(|&Point2D(x, y)| x < self.num_cols && y < self.num_rows)
+            .filter(move |point| self.heights[point] <= this_height + 1)
+    }
+}
+
+impl Graph for HeightMap<usize> {
+    type Node = Point2D<usize>;
 
-        potential_neighbours
-            .into_iter()
-            .filter(|point| *self.heights.get(point).unwrap() <= this_height + 1)
-            .collect::<Vec<_>>()
+    fn neighbours(&self, node: &Self::Node) -> impl IntoIterator<Item = Self::Node> {
+        self.climbable_neighbours(*node)
     }
 }
 
 impl FromStr for HeightMap<usize> {
-    type Err = anyhow::Error;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (s_row, s_col, _s_char) = s
@@ -50,7 +50,7 @@ impl FromStr for HeightMap<usize> {
                     .map(move |(col_num, char)| (row_num, col_num, char))
             })
             .find(|(_row_num, _col_num, char)| *char == 'S')
-            .context("no starting point found")?;
+            .ok_or_else(|| AocError::missing_input("no starting point found"))?;
         let start = Point2D(s_col, s_row);
 
         let (g_row, g_col, _g_char) = s
@@ -61,29 +61,32 @@ impl FromStr for HeightMap<usize> {
                     .map(move |(col_num, char)| (row_num, col_num, char))
             })
             .find(|(_row_num, _col_num, char)| *char == 'E')
-            .context("no end point found")?;
+            .ok_or_else(|| AocError::missing_input("no end point found"))?;
         let goal = Point2D(g_col, g_row);
 
-        let num_cols = s.lines().next().context("at least one row")?.len();
+        let num_cols = s.lines().next().map_or(0, str::len);
 
         let num_rows = s.lines().count();
 
-        let heights: HashMap<Point2D<usize>, u32> = s
-            .lines()
-            .enumerate()
-            .flat_map(|(row_num, row)| {
-                row.chars()
-                    .map(|point| match point {
-                        'S' => 'a',
-                        'E' => 'z',
-                        i @ 'a'..='z' => i,
-                        _ => unreachable!(),
-                    })
-                    .map(|point| point as u32 - 97)
-                    .enumerate()
-                    .map(move |(col_num, height)| (Point2D(col_num, row_num), height))
-            })
-            .collect();
+        let mut heights: FastMap<Point2D<usize>, u32> = FastMap::default();
+        for (row_num, row) in s.lines().enumerate() {
+            if row.len() != num_cols {
+                return Err(AocError::invalid_input("the map isn't rectangular"));
+            }
+            for (col_num, point) in row.char_indices() {
+                let height = match point {
+                    'S' => 'a',
+                    'E' => 'z',
+                    i @ 'a'..='z' => i,
+                    _ => {
+                        return Err(ParseError::at(row, &row[col_num..], "not a height")
+                            .on_line(row_num + 1)
+                            .into())
+                    }
+                };
+                heights.insert(Point2D(col_num, row_num), height as u32 - 97);
+            }
+        }
         Ok(Self {
             start,
             goal,
@@ -94,48 +97,37 @@ impl FromStr for HeightMap<usize> {
     }
 }
 
-pub fn p1(file: &str) -> anyhow::Result<u32> {
-    let height_map = HeightMap::from_str(file)?;
-    let shortest_path = astar::astar(
-        &height_map.start,
-        |point| {
-            height_map
-                .climbable_neighbours(*point)
-                .into_iter()
-                .map(|point| (point, 1))
-                .collect::<Vec<_>>()
-        },
-        |point| 26 - height_map.heights.get(point).unwrap(),
-        |point| *point == height_map.goal,
-    )
-    .expect("there must be at least one shortest path")
-    .1;
-    Ok(shortest_path)
+pub fn parse(file: &str) -> aoc2022lib::Result<HeightMap<usize>> {
+    HeightMap::from_str(file)
 }
 
-pub fn p2(file: &str) -> anyhow::Result<u32> {
-    let height_map = HeightMap::from_str(file)?;
-    height_map
+pub fn part1(height_map: &HeightMap<usize>) -> aoc2022lib::Result<u32> {
+    let search = bfs(height_map, [height_map.start], |point| {
+        *point == height_map.goal
+    });
+    let path = search.goal_path().ok_or(AocError::NoSolution)?;
+    // the path includes the start
+    Ok(u32::try_from(path.len() - 1)?)
+}
+
+pub fn part2(height_map: &HeightMap<usize>) -> aoc2022lib::Result<u32> {
+    // searching from all the lowest points at once finds the closest one
+    let lowest_points = height_map
         .heights
         .iter()
         .filter(|&(_point, height)| *height == 0)
-        .filter_map(|(lowest_point, _height)| {
-            astar::astar(
-                lowest_point,
-                |point| {
-                    height_map
-                        .climbable_neighbours(*point)
-                        .into_iter()
-                        .map(|point| (point, 1))
-                        .collect::<Vec<_>>()
-                },
-                |point| 26 - height_map.heights.get(point).unwrap(),
-                |point| *point == height_map.goal,
-            )
-        })
-        .map(|(_path, path_length)| path_length)
-        .min()
-        .context("there must be at least one shortest path")
+        .map(|(point, _height)| *point);
+    let search = bfs(height_map, lowest_points, |point| *point == height_map.goal);
+    let shortest_path = search.goal_distance().ok_or(AocError::NoSolution)?;
+    Ok(u32::try_from(shortest_path)?)
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
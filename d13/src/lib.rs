@@ -1,4 +1,4 @@
-use aoc2022lib::impl_from_str_from_nom_parser;
+use aoc2022lib::{impl_from_str_from_nom_parser, parse::ParseError, AocError};
 use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
 use nom::{
@@ -10,7 +10,7 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, Eq)]
+#[derive(Debug, Clone, Eq)]
 enum Item {
     List(Vec<Item>),
     Integer(u8),
@@ -73,12 +73,54 @@ impl Ord for Item {
     }
 }
 
-pub fn p1(file: &str) -> anyhow::Result<usize> {
-    let pairs = file
-        .split("\n\n")
-        .map(|pair| pair.split_once('\n').unwrap().into())
-        .map(|pair: [&str; 2]| pair.map(|part| Item::from_str(part).unwrap()));
+// [1,1,3,1,1]
+// [1,1,5,1,1]
+fn pair(pair: &str) -> Result<[Item; 2], ParseError> {
+    let (left, right) = pair
+        .split_once('\n')
+        .ok_or_else(|| ParseError::new(pair, "not a pair of packets"))?;
+    Ok([
+        Item::from_str(left)?,
+        Item::from_str(right).map_err(|err| err.on_line(2))?,
+    ])
+}
+
+/// How deeply lists may be nested, parsing and comparing them recurses once per level
+const MAX_DEPTH: usize = 100;
+
+fn max_depth(file: &str) -> usize {
+    let mut depth = 0usize;
+    let mut max_depth = 0;
+    for chr in file.chars() {
+        match chr {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        max_depth = max_depth.max(depth);
+    }
+    max_depth
+}
+
+/// The packets, in pairs
+pub struct Pairs(Vec<[Item; 2]>);
+
+pub fn parse(file: &str) -> aoc2022lib::Result<Pairs> {
+    if max_depth(file) > MAX_DEPTH {
+        return Err(AocError::invalid_input(format!(
+            "lists nested more than {MAX_DEPTH} deep"
+        )));
+    }
+    let mut line = 1;
+    let mut pairs = Vec::new();
+    for part in file.split("\n\n") {
+        pairs.push(pair(part).map_err(|err| err.on_line(line))?);
+        line += part.lines().count() + 1;
+    }
+    Ok(Pairs(pairs))
+}
 
+pub fn part1(Pairs(pairs): &Pairs) -> aoc2022lib::Result<usize> {
     let res = (1..)
         .zip(pairs)
         .filter_map(|(idx, [left, right])| if left < right { Some(idx) } else { None })
@@ -86,33 +128,39 @@ pub fn p1(file: &str) -> anyhow::Result<usize> {
 
     Ok(res)
 }
-pub fn p2(file: &str) -> anyhow::Result<usize> {
-    let dividers = ["[[2]]", "[[6]]"];
+pub fn part2(Pairs(pairs): &Pairs) -> aoc2022lib::Result<usize> {
+    let [two, six] = ["[[2]]", "[[6]]"].map(Item::from_str);
+    let dividers = [two?, six?];
 
-    let mut packets: Vec<Item> = file
-        .lines()
-        .filter(|line| !line.is_empty())
-        .chain(dividers)
-        .map(Item::from_str)
-        .collect::<Result<Vec<_>, _>>()?;
+    let mut packets: Vec<Item> = pairs.iter().flatten().chain(&dividers).cloned().collect();
     packets.sort_unstable();
 
-    let res: usize = dividers
-        .map(|divider| divider.parse::<Item>().unwrap())
-        .map(|divider| packets.binary_search(&divider).unwrap() + 1)
-        .iter()
-        .product();
+    let mut res = 1;
+    for divider in &dividers {
+        let position = packets
+            .binary_search(divider)
+            .map_err(|_| AocError::NoSolution)?;
+        res *= position + 1;
+    }
 
     Ok(res)
 }
 
+pub fn p1(file: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?)
+}
+
 #[allow(clippy::needless_pass_by_value)]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs::read_to_string;
     use test_case::test_case;
     use Item::{Integer as I, List as L};
-    use std::fs::read_to_string;
 
     #[test_case(I(5), I(6))]
     #[test_case(I(5), L(vec![I(6)]); "Integer VS List")]
@@ -149,6 +197,12 @@ mod tests {
         assert_eq!(s.parse::<Item>().unwrap(), item);
     }
 
+    #[test]
+    fn too_deep() {
+        let deep = format!("{}{}\n[]", "[".repeat(200_000), "]".repeat(200_000));
+        assert!(matches!(parse(&deep), Err(AocError::InvalidInput(_))));
+    }
+
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
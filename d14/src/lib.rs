@@ -1,31 +1,31 @@
-use std::{
-    cmp::{max, min},
-    collections::HashMap,
-    fmt::Display,
-    iter::repeat,
-    str::FromStr,
+use std::{fmt::Display, iter::repeat, str::FromStr};
+
+use aoc2022lib::{
+    grid::RenderOptions,
+    impl_from_str_from_nom_parser,
+    lines::polyline,
+    parse::Parse,
+    points::{Bounds2D, Point2D},
+    render::{Canvas, Pixel, Rgb},
+    simulation::{Driver, Simulation},
+    AocError, SparseGrid,
 };
-
-use anyhow::bail;
-use aoc2022lib::{impl_from_str_from_nom_parser, points::Point2D};
 use derive_deref::Deref;
-use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::{char, u32},
+    character::complete::{char, i32},
     combinator::map,
     multi::separated_list1,
     sequence::separated_pair,
     IResult,
 };
 
-type Point = Point2D<u32>;
+type Point = Point2D<i32>;
 
-struct Border {
-    left: u32,
-    right: u32,
-    down: u32,
-}
+const SAND_SOURCE: Point = Point2D(500, 0);
+
+/// Where a unit of sand tries to fall, in order of preference
+const FALL_DIRECTIONS: [Point; 3] = [Point2D(0, 1), Point2D(-1, 1), Point2D(1, 1)];
 
 #[derive(Clone, Copy)]
 enum UnitType {
@@ -33,14 +33,41 @@ enum UnitType {
     Stone,
 }
 
-struct Cave {
-    borders: Border,
-    resting: HashMap<Point, UnitType>,
+impl Pixel for UnitType {
+    fn glyph(&self) -> char {
+        match self {
+            UnitType::Sand => 'o',
+            UnitType::Stone => '#',
+        }
+    }
+
+    fn colour(&self) -> Rgb {
+        match self {
+            UnitType::Sand => Rgb(230, 190, 90),
+            UnitType::Stone => Rgb(130, 130, 130),
+        }
+    }
+}
+
+/// The rocks, and later the sand resting on them
+#[derive(Clone)]
+pub struct Cave {
+    resting: SparseGrid<UnitType>,
+}
+
+impl Cave {
+    /// Around everything resting in the cave and the source of sand
+    fn bounds(&self) -> Bounds2D<i32> {
+        let source = Bounds2D::new(SAND_SOURCE, SAND_SOURCE);
+        self.resting
+            .bounds()
+            .map_or(source, |bounds| bounds.union(&source))
+    }
 }
 
 // 498,4
 fn point(i: &str) -> IResult<&str, Point> {
-    map(separated_pair(u32, char(','), u32), |(x, y)| Point2D(x, y))(i)
+    map(separated_pair(i32, char(','), i32), Point2D::from)(i)
 }
 
 #[derive(Deref)]
@@ -54,146 +81,136 @@ fn path(i: &str) -> IResult<&str, Path> {
 impl_from_str_from_nom_parser!(path, Path);
 
 impl FromStr for Cave {
-    type Err = anyhow::Error;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let paths = s
-            .lines()
-            .map(Path::from_str)
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let borders = {
-            let (left, right) = paths
-                .iter()
-                .flat_map(|path| path.iter())
-                .map(Point2D::x)
-                .minmax()
-                .into_option()
-                .unwrap();
-            let down = paths
-                .iter()
-                .flat_map(|path| path.iter())
-                .map(Point2D::y)
-                .max()
-                .unwrap();
-            Border { left, right, down }
-        };
+        let paths = Vec::<Path>::parse(s)?;
 
-        let mut resting: HashMap<Point, UnitType> = HashMap::new();
+        let mut resting = SparseGrid::new();
         for path in paths {
-            // FIXME use array_windows once that's stabilized
-            // https://github.com/rust-lang/rust/issues/75027
-            for pair in path.windows(2) {
-                let &[p1, p2] = pair else { unreachable!() };
-                let points = all_points_between_two_points(p1, p2)?;
-                resting.extend(points.zip(repeat(UnitType::Stone)));
-            }
+            let Some(points) = polyline(&path) else {
+                return Err(AocError::invalid_input(format!(
+                    "points are not on a line: {:?}",
+                    *path
+                )));
+            };
+            resting.extend(points.zip(repeat(UnitType::Stone)));
         }
 
-        Ok(Self { borders, resting })
-    }
-}
-
-fn all_points_between_two_points(
-    p1 @ Point2D(x1, y1): Point,
-    p2 @ Point2D(x2, y2): Point,
-) -> anyhow::Result<Box<dyn Iterator<Item = Point2D<u32, u32>>>> {
-    if y1 == y2 {
-        let res = (min(x1, x2)..=max(x1, x2))
-            .zip(repeat(y1))
-            .map(|(x, y)| Point2D(x, y));
-        Ok(Box::new(res))
-    } else if x1 == x2 {
-        let res = repeat(x1)
-            .zip(min(y1, y2)..=max(y1, y2))
-            .map(|(x, y)| Point2D(x, y));
-        Ok(Box::new(res))
-    } else {
-        bail!("points are not on a line: {p1:#?}, {p2:#?}");
+        Ok(Self { resting })
     }
 }
 
 impl Display for Cave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut res = String::new();
-        for y in 0..=(self.borders.down + 2) {
-            for x in (self.borders.left - 2)..=(self.borders.right + 2) {
-                let char = match self.resting.get(&Point2D(x, y)) {
-                    Some(UnitType::Stone) => '#',
-                    Some(UnitType::Sand) => 'o',
-                    None => '.',
-                };
-                res.push(char);
-            }
-            res.push('\n');
-        }
-        write!(f, "{res}")
+        let options = RenderOptions {
+            padding: 2,
+            viewport: Some(self.bounds()),
+            ..Default::default()
+        };
+        let canvas = Canvas::from_grid(&self.resting, &options, Option::<&UnitType>::copied);
+        write!(f, "{canvas}")
     }
 }
 
-pub fn p1(file: &str) -> anyhow::Result<u32> {
-    let mut cave = file.parse::<Cave>()?;
+/// What's underneath the rocks
+#[derive(Clone, Copy)]
+enum Bottom {
+    /// Sand leaving these bounds keeps falling forever
+    Abyss(Bounds2D<i32>),
+    /// Sand comes to rest right above this height
+    Floor(i32),
+}
 
-    let init_sand = Point2D(500, 0);
-    let mut sands = 0;
-    'outer: loop {
-        let mut sand = init_sand;
+/// A unit of sand falling from the source each step
+struct SandFall {
+    cave: Cave,
+    bottom: Bottom,
+    num_resting: u32,
+}
 
-        while let Some(next_sand) = [
-            Point2D(sand.0, sand.1 + 1),
-            Point2D(sand.0 - 1, sand.1 + 1),
-            Point2D(sand.0 + 1, sand.1 + 1),
-        ]
-        .into_iter()
-        .find(|point| !cave.resting.contains_key(point))
-        {
-            let sand_in_bounds = (cave.borders.left..=cave.borders.right).contains(&sand.0)
-                && (..cave.borders.down).contains(&sand.1);
+/// A unit of sand fell past the rocks, all the ones after it would too
+struct LostToAbyss;
 
-            if !sand_in_bounds {
-                break 'outer;
-            }
-            sand = next_sand;
+impl SandFall {
+    fn new(cave: Cave, bottom: Bottom) -> Self {
+        Self {
+            cave,
+            bottom,
+            num_resting: 0,
         }
-        cave.resting.insert(sand, UnitType::Sand);
-        sands += 1;
     }
+}
+
+impl Simulation for SandFall {
+    type Snapshot = Cave;
+    type Error = LostToAbyss;
 
-    Ok(sands)
-}
-pub fn p2(file: &str) -> anyhow::Result<u32> {
-    let mut cave = file.parse::<Cave>()?;
-
-    let init_sand = Point2D(500, 0);
-    let mut sands = 0;
-    while !cave.resting.contains_key(&init_sand) {
-        let mut sand = init_sand;
-        // while:
-        // the next point downwards isn't on the Ultimate Lower Border
-        while sand.1 + 1 < cave.borders.down + 2 {
-            // and there's somewhere to fall to
-            // comment: these two conditions (where+if let) should really be
-            // checked simulatenously but this is not stable yet
-            // (Reference: `https://github.com/rust-lang/rust/issues/53667`)
-            if let Some(next_sand) = [
-                Point2D(sand.0, sand.1 + 1),
-                Point2D(sand.0 - 1, sand.1 + 1),
-                Point2D(sand.0 + 1, sand.1 + 1),
-            ]
+    fn step(&mut self) -> Result<(), LostToAbyss> {
+        let mut sand = SAND_SOURCE;
+        while let Some(next_sand) = FALL_DIRECTIONS
+            .map(|direction| sand + direction)
             .into_iter()
-            .find(|point| !cave.resting.contains_key(point))
-            {
-                // fall
-                sand = next_sand;
-            } else {
-                break;
+            .find(|point| !self.cave.resting.contains(point))
+        {
+            match self.bottom {
+                // nothing can stop the sand once it's fallen past the rocks
+                Bottom::Abyss(bounds) if !bounds.contains(&sand) => return Err(LostToAbyss),
+                // the next point downwards is on the Ultimate Lower Border
+                Bottom::Floor(floor) if next_sand.1 == floor => break,
+                _ => sand = next_sand,
             }
         }
-        cave.resting.insert(sand, UnitType::Sand);
-        sands += 1;
+        self.cave.resting.insert(sand, UnitType::Sand);
+        self.num_resting += 1;
+        Ok(())
+    }
+
+    fn is_done(&self) -> bool {
+        self.cave.resting.contains(&SAND_SOURCE)
+    }
+
+    fn snapshot(&self) -> Cave {
+        self.cave.clone()
     }
+}
+
+/// How many units of sand come to rest before the source is blocked or sand is lost to the abyss
+fn num_resting(cave: Cave, bottom: Bottom) -> u32 {
+    let mut driver = Driver::new(SandFall::new(cave, bottom));
+    let (Ok(_) | Err(LostToAbyss)) = driver.run();
+    driver.simulation().num_resting
+}
+
+pub fn parse(file: &str) -> aoc2022lib::Result<Cave> {
+    file.parse()
+}
+
+pub fn part1(cave: &Cave) -> aoc2022lib::Result<u32> {
+    let cave = cave.clone();
+    let bottom = Bottom::Abyss(cave.bounds());
+
+    Ok(num_resting(cave, bottom))
+}
+pub fn part2(cave: &Cave) -> aoc2022lib::Result<u32> {
+    let cave = cave.clone();
+    let floor = cave
+        .bounds()
+        .max
+        .1
+        .checked_add(2)
+        .ok_or(AocError::Overflow)?;
+    let bottom = Bottom::Floor(floor);
+
+    Ok(num_resting(cave, bottom))
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
 
-    Ok(sands)
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
@@ -1,11 +1,14 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
+use std::str::FromStr;
+
+use aoc2022lib::{
+    collections::{FastBuildHasher, FastMap},
+    impl_from_str_from_nom_parser,
+    interval::{Interval, IntervalSet},
+    lines::manhattan_circle,
+    points::{Bounds2D, Point2D},
+    AocError,
 };
 
-use aoc2022lib::{impl_from_str_from_nom_parser, points::Point2D};
-
-use anyhow::Context;
 use derive_deref::Deref;
 use itertools::Itertools;
 use nom::{
@@ -19,16 +22,6 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 type Point = Point2D<i32>;
 
-trait ManhattanDistance {
-    fn manhattan_distance(self, other: Self) -> u32;
-}
-
-impl ManhattanDistance for Point {
-    fn manhattan_distance(self, other: Self) -> u32 {
-        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
-    }
-}
-
 // x=2, y=18
 fn point(i: &str) -> IResult<&str, Point> {
     map(
@@ -37,7 +30,7 @@ fn point(i: &str) -> IResult<&str, Point> {
             tag(", "),
             preceded(tag("y="), i32),
         ),
-        |(x, y)| Point2D(x, y),
+        Point2D::from,
     )(i)
 }
 
@@ -60,17 +53,29 @@ fn sensor_with_beacon(i: &str) -> IResult<&str, SensorWithBeacon> {
 
 impl_from_str_from_nom_parser!(sensor_with_beacon, SensorWithBeacon);
 
+/// Each sensor's position, along with the closest beacon's
 #[derive(Deref)]
-struct SensorsWithBeacons(HashMap<SensorPosition, BeaconPosition>);
+pub struct SensorsWithBeacons(FastMap<SensorPosition, BeaconPosition>);
+
+/// Small enough for distances between points and the rows around them to fit an `i32`
+const MAX_COORDINATE: i32 = i32::MAX / 8;
 
 impl FromStr for SensorsWithBeacons {
-    type Err = anyhow::Error;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut sensors_with_beacons = HashMap::with_capacity(s.lines().count());
-
-        for line in s.lines() {
-            let SensorWithBeacon(sensor_pos, beacon_pos) = SensorWithBeacon::from_str(line)?;
+        let mut sensors_with_beacons =
+            FastMap::with_capacity_and_hasher(s.lines().count(), FastBuildHasher::default());
+
+        for (idx, line) in s.lines().enumerate() {
+            let SensorWithBeacon(sensor_pos, beacon_pos) =
+                SensorWithBeacon::from_str(line).map_err(|err| err.on_line(idx + 1))?;
+            if [sensor_pos, beacon_pos]
+                .iter()
+                .any(|&Point2D(x, y)| x.abs().max(y.abs()) > MAX_COORDINATE)
+            {
+                return Err(AocError::Overflow);
+            }
             sensors_with_beacons.insert(sensor_pos, beacon_pos);
         }
 
@@ -78,106 +83,91 @@ impl FromStr for SensorsWithBeacons {
     }
 }
 
-#[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
-pub fn p1(file: &str, analyzed_row_num: i32) -> anyhow::Result<usize> {
-    let sensors_with_beacons = SensorsWithBeacons::from_str(file)?;
+pub fn parse(file: &str) -> aoc2022lib::Result<SensorsWithBeacons> {
+    SensorsWithBeacons::from_str(file)
+}
+
+pub fn part1(
+    sensors_with_beacons: &SensorsWithBeacons,
+    analyzed_row_num: i32,
+) -> aoc2022lib::Result<usize> {
+    let analyzed_row_num = analyzed_row_num.clamp(-MAX_COORDINATE, MAX_COORDINATE);
 
-    let mut impossible_locations_of_distress_beacon: HashSet<i32> = sensors_with_beacons
+    let mut impossible_locations_of_distress_beacon: IntervalSet<i32> = sensors_with_beacons
         .par_iter()
         .filter_map(|(signal, beacon)| {
-            let distance_to_beacon = signal.manhattan_distance(*beacon);
-            let distance_to_analyzed_row = signal.1.abs_diff(analyzed_row_num);
-
-            match distance_to_analyzed_row.cmp(&distance_to_beacon) {
-                std::cmp::Ordering::Greater => None,
-                std::cmp::Ordering::Equal => Some(signal.0..=signal.0),
-                std::cmp::Ordering::Less => {
-                    let width_of_covered_space_on_the_analyzed_row =
-                        distance_to_beacon - distance_to_analyzed_row;
-
-                    Some(
-                        (signal.0 - width_of_covered_space_on_the_analyzed_row as i32)
-                            ..=(signal.0 + width_of_covered_space_on_the_analyzed_row as i32),
-                    )
-                }
-            }
+            // how far to the sides of the sensor its range still reaches on the analyzed row
+            let reach = signal.manhattan(*beacon) - (signal.1 - analyzed_row_num).abs();
+            Interval::new(signal.0 - reach, signal.0 + reach)
         })
-        .flatten()
+        .collect::<Vec<_>>()
+        .into_iter()
         .collect();
 
     // "is `x=2,y=10` a "position where a beacon cannot be present"?"
-    // use drain_filter when it's stable
-    // https://github.com/rust-lang/rust/issues/43244
     for beacon in sensors_with_beacons.values() {
         if beacon.1 == analyzed_row_num {
-            impossible_locations_of_distress_beacon.remove(&beacon.0);
+            impossible_locations_of_distress_beacon.remove(beacon.0);
         }
     }
 
-    Ok(impossible_locations_of_distress_beacon.len())
+    Ok(usize::try_from(
+        impossible_locations_of_distress_beacon.total_length(),
+    )?)
 }
 
 #[derive(Deref)]
-struct SensorsWithDistances(HashMap<SensorPosition, u32>);
-
-impl FromStr for SensorsWithDistances {
-    type Err = anyhow::Error;
+struct SensorsWithDistances(FastMap<SensorPosition, i32>);
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let sensors_with_distances = s
-            .parse::<SensorsWithBeacons>()?
+impl From<&SensorsWithBeacons> for SensorsWithDistances {
+    fn from(sensors_with_beacons: &SensorsWithBeacons) -> Self {
+        let sensors_with_distances = sensors_with_beacons
             .par_iter()
             .map(|(sensor_coords, beacon_coords)| {
-                let distance = sensor_coords.manhattan_distance(*beacon_coords);
+                let distance = sensor_coords.manhattan(*beacon_coords);
                 (*sensor_coords, distance)
             })
-            .collect::<HashMap<_, _>>();
+            .collect::<FastMap<_, _>>();
 
-        Ok(Self(sensors_with_distances))
+        Self(sensors_with_distances)
     }
 }
 
-#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
-#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
-pub fn p2(file: &str, search_space_side_size: i32) -> anyhow::Result<u64> {
-    let sensors_with_distances = SensorsWithDistances::from_str(file)?;
+#[allow(clippy::cast_sign_loss)]
+pub fn part2(
+    sensors_with_beacons: &SensorsWithBeacons,
+    search_space_side_size: i32,
+) -> aoc2022lib::Result<u64> {
+    let sensors_with_distances = SensorsWithDistances::from(sensors_with_beacons);
+    let search_space = Bounds2D::new(
+        Point2D(0, 0),
+        Point2D(search_space_side_size, search_space_side_size),
+    );
     let distress_beacon = sensors_with_distances
         .par_iter()
-        .flat_map(|(point, radius)| {
-            // the vertices
-            let radius = *radius as i32;
-            let left = point.0 - radius - 1;
-            let right = point.0 + radius + 1;
-            let up = point.1 - radius - 1;
-            let down = point.1 + radius + 1;
-            // the sides (moving clockwise)
-            let left_upper = (left..point.0).zip((up..point.1).rev());
-            let right_upper = (point.0..right).zip(up..point.1);
-            let right_lower = ((point.0..right).rev()).zip(point.1..down);
-            let left_lower = ((left..point.0).rev()).zip((point.1..down).rev());
-
-            left_upper
-                .chain(right_upper)
-                .chain(right_lower)
-                .chain(left_lower)
-                .collect_vec()
-        })
-        .filter(|(x, y)| {
-            0 <= *x && *x <= search_space_side_size && 0 <= *y && *y <= search_space_side_size
-        })
-        .map(|(x, y)| Point2D(x, y))
+        // the only uncovered point has to be right outside the range of some sensor
+        .flat_map(|(point, &radius)| manhattan_circle(*point, radius + 1).collect_vec())
+        .filter(|point| search_space.contains(point))
         .find_any(|point| {
             sensors_with_distances
                 .par_iter()
                 .all(|(sensor, distance_to_nearest_beacon)| {
-                    sensor.manhattan_distance(*point) > *distance_to_nearest_beacon
+                    sensor.manhattan(*point) > *distance_to_nearest_beacon
                 })
         })
-        .context("No distress beacon found")?;
+        .ok_or(AocError::NoSolution)?;
     let tuning_frequency: u64 = 4_000_000u64 * distress_beacon.0 as u64 + distress_beacon.1 as u64;
     Ok(tuning_frequency)
 }
 
+pub fn p1(file: &str, analyzed_row_num: i32) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?, analyzed_row_num)
+}
+
+pub fn p2(file: &str, search_space_side_size: i32) -> aoc2022lib::Result<u64> {
+    part2(&parse(file)?, search_space_side_size)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,15 +1,21 @@
-use std::{cmp::max, collections::HashSet, fmt::Display};
+use std::{cmp::max, convert::Infallible, fmt::Display};
 
-use anyhow::anyhow;
-use itertools::Itertools;
+use aoc2022lib::{
+    grid::RenderOptions,
+    parse::ParseError,
+    points::{Bounds2D, Point2D},
+    render::Canvas,
+    simulation::{Driver, Simulation},
+    AocError, SparseGrid,
+};
 
-use aoc2022lib::points::Point2D;
+type Point = Point2D<i64>;
 
 #[derive(Clone, Copy)]
 struct Rock {
-    points: [Point2D<u8, u64>; 5],
-    width: u8,
-    height: u64,
+    points: [Point; 5],
+    width: i64,
+    height: i64,
 }
 
 #[derive(Clone, Copy)]
@@ -49,13 +55,13 @@ enum JetStreamDirection {
 }
 
 impl TryFrom<char> for JetStreamDirection {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
-    fn try_from(value: char) -> anyhow::Result<Self> {
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
             '<' => Ok(Self::Left),
             '>' => Ok(Self::Right),
-            chr => Err(anyhow!("Invalid char: '{}'", chr)),
+            chr => Err(ParseError::new(&chr.to_string(), "expected '<' or '>'")),
         }
     }
 }
@@ -66,91 +72,132 @@ impl TryFrom<char> for JetStreamDirection {
 // into a HashSet, and terminate after having found a state already seen
 // after that, see how many rows were added during the cycle, and calculate
 // the total num of rows after `num_rounds` rounds based on that
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Chamber {
-    width: u8,
-    height: u64,
-    occupied_points: HashSet<Point2D<u8, u64>>,
+    width: i64,
+    height: i64,
+    occupied_points: SparseGrid<(), i64>,
 }
 
 impl Chamber {
-    fn new(width: u8) -> Self {
+    fn new(width: i64) -> Self {
         Self {
             width,
             ..Default::default()
         }
     }
 
-    fn contains(&self, q: &Point2D<u8, u64>) -> bool {
+    fn contains(&self, q: &Point) -> bool {
         self.occupied_points.contains(q)
     }
 
-    fn trim_to(&mut self, height_to_trim_to: u64) {
+    fn trim_to(&mut self, height_to_trim_to: i64) {
         self.occupied_points
-            .retain(|point| point.1 > self.height - height_to_trim_to);
+            .retain(|point, ()| point.1 > self.height - height_to_trim_to);
     }
 
-    const MAX_HEIGHT_BEFORE_TRIMMING: u64 = 1024 * 1024 * 1024;
-    const HEIGHT_TO_TRIM_TO: u64 = 512;
-    fn add_rock(&mut self, rock: Rock, rock_position_relative: Point2D<u8, u64>) {
-        self.occupied_points
-            .extend(&rock.points.map(|point| point + rock_position_relative));
+    const MAX_HEIGHT_BEFORE_TRIMMING: i64 = 1024 * 1024 * 1024;
+    const HEIGHT_TO_TRIM_TO: i64 = 512;
+    fn add_rock(&mut self, rock: Rock, rock_position_relative: Point) {
+        self.occupied_points.extend(
+            rock.points
+                .map(|point| (point + rock_position_relative, ())),
+        );
         self.height = max(self.height, rock_position_relative.1 + rock.height);
         if self.height > Self::MAX_HEIGHT_BEFORE_TRIMMING {
             self.trim_to(Self::HEIGHT_TO_TRIM_TO);
         }
     }
 
-    fn height(&self) -> u64 {
+    fn height(&self) -> i64 {
         self.height
     }
 }
 
 impl Display for Chamber {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let res = ((self.height().saturating_sub(20))..=self.height())
-            .rev()
-            .map(|y| {
-                (0..=self.width)
-                    .map(move |x| (Point2D(x, y)))
-                    .map(|point| {
-                        if self.occupied_points.contains(&point) {
-                            '#'
-                        } else {
-                            '.'
-                        }
-                    })
-                    .join("")
-            })
-            .join("\n");
-
-        write!(f, "{res}")
-    }
-}
-
-#[allow(clippy::items_after_statements)]
-fn tetris(file: &str, num_rounds: usize) -> anyhow::Result<u64> {
-    let mut chamber = Chamber::new(7);
-    use RockType as RT;
-    let rocks = vec![RT::Minus, RT::Plus, RT::RightL, RT::I, RT::Square]
-        .into_iter()
-        .map(Rock::new)
-        .cycle()
-        .take(num_rounds);
-    let mut pushes = file
-        .chars()
-        .map(JetStreamDirection::try_from)
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .cycle();
-
-    for rock in rocks {
-        let spawn_height = chamber.height() + 3;
+        // only the top of the tower
+        let options = RenderOptions {
+            viewport: Some(Bounds2D::new(
+                Point2D(0, (self.height() - 20).max(0)),
+                Point2D(self.width - 1, self.height()),
+            )),
+            y_up: true,
+            ..Default::default()
+        };
+        let canvas = Canvas::from_grid(&self.occupied_points, &options, |cell| cell.is_some());
+        write!(f, "{canvas}")
+    }
+}
+
+/// Which way the jets push the rocks, repeating once it runs out
+pub struct JetPattern(Vec<JetStreamDirection>);
+
+pub fn parse(file: &str) -> aoc2022lib::Result<JetPattern> {
+    let pushes = file
+        .trim_end()
+        .char_indices()
+        .map(|(idx, chr)| {
+            JetStreamDirection::try_from(chr)
+                .map_err(|err| ParseError::at(file, &file[idx..], err.reason))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if pushes.is_empty() {
+        return Err(AocError::missing_input("jet pattern"));
+    }
+    Ok(JetPattern(pushes))
+}
+
+/// A rock falling into the chamber each step, pushed around by the jets until it comes to rest
+struct Tetris<'a> {
+    chamber: Chamber,
+    rocks: Vec<Rock>,
+    pushes: &'a [JetStreamDirection],
+    num_pushes: usize,
+    num_rocks: usize,
+    num_rounds: usize,
+}
+
+impl<'a> Tetris<'a> {
+    fn new(JetPattern(pushes): &'a JetPattern, num_rounds: usize) -> Self {
+        use RockType as RT;
+        let rocks = [RT::Minus, RT::Plus, RT::RightL, RT::I, RT::Square]
+            .into_iter()
+            .map(Rock::new)
+            .collect();
+
+        Self {
+            chamber: Chamber::new(7),
+            rocks,
+            pushes,
+            num_pushes: 0,
+            num_rocks: 0,
+            num_rounds,
+        }
+    }
+
+    fn next_push(&mut self) -> JetStreamDirection {
+        let push = self.pushes[self.num_pushes % self.pushes.len()];
+        self.num_pushes += 1;
+        push
+    }
+}
+
+impl Simulation for Tetris<'_> {
+    type Snapshot = Chamber;
+    type Error = Infallible;
+
+    fn step(&mut self) -> Result<(), Infallible> {
+        let rock = self.rocks[self.num_rocks % self.rocks.len()];
+        self.num_rocks += 1;
+
+        let spawn_height = self.chamber.height() + 3;
         let mut rock_position_relative = Point2D(2, spawn_height);
         loop {
-            println!("{chamber}\n");
+            let push = self.next_push();
+            let chamber = &self.chamber;
             // jet stream
-            match pushes.next().unwrap() {
+            match push {
                 JetStreamDirection::Left => {
                     if rock_position_relative.0 > 0
                         && rock
@@ -179,7 +226,7 @@ fn tetris(file: &str, num_rounds: usize) -> anyhow::Result<u64> {
             // come to rest if:
             // 1) arrived at the lowest point
             if rock_position_relative.1 == 0 {
-                chamber.add_rock(rock, rock_position_relative);
+                self.chamber.add_rock(rock, rock_position_relative);
                 break;
             }
             // 2) there's a rock point directly underneath
@@ -192,28 +239,53 @@ fn tetris(file: &str, num_rounds: usize) -> anyhow::Result<u64> {
                     )
                 })
                 .iter()
-                .any(|point| chamber.contains(point));
+                .any(|point| self.chamber.contains(point));
             if rock_stops {
-                chamber.add_rock(rock, rock_position_relative);
+                self.chamber.add_rock(rock, rock_position_relative);
                 break;
                 //fall
             }
             rock_position_relative.1 -= 1;
         }
+        Ok(())
+    }
+
+    fn is_done(&self) -> bool {
+        self.num_rocks == self.num_rounds
+    }
+
+    fn snapshot(&self) -> Chamber {
+        self.chamber.clone()
     }
-    Ok(chamber.height())
 }
 
-pub fn p_mid(file: &str) -> anyhow::Result<u64> {
-    tetris(file, 1_000_000)
+fn tetris(jet_pattern: &JetPattern, num_rounds: usize) -> aoc2022lib::Result<u64> {
+    let tetris = Tetris::new(jet_pattern, num_rounds);
+    Ok(u64::try_from(Driver::new(tetris).run()?.chamber.height())?)
+}
+
+pub fn part_mid(jet_pattern: &JetPattern) -> aoc2022lib::Result<u64> {
+    tetris(jet_pattern, 1_000_000)
+}
+
+pub fn part1(jet_pattern: &JetPattern) -> aoc2022lib::Result<u64> {
+    tetris(jet_pattern, 2022)
+}
+
+pub fn part2(jet_pattern: &JetPattern) -> aoc2022lib::Result<u64> {
+    tetris(jet_pattern, 1_000_000_000_000)
+}
+
+pub fn p_mid(file: &str) -> aoc2022lib::Result<u64> {
+    part_mid(&parse(file)?)
 }
 
-pub fn p1(file: &str) -> anyhow::Result<u64> {
-    tetris(file, 2022)
+pub fn p1(file: &str) -> aoc2022lib::Result<u64> {
+    part1(&parse(file)?)
 }
 
-pub fn p2(file: &str) -> anyhow::Result<u64> {
-    tetris(file, 1_000_000_000_000)
+pub fn p2(file: &str) -> aoc2022lib::Result<u64> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
@@ -237,7 +309,7 @@ mod tests {
         assert_eq!(p2(&inp).unwrap(), 1_514_285_714_288);
     }
     #[test]
-    #[ignore]
+    #[ignore = "not solved yet"]
     fn real_p2() {
         let inp = read_to_string("inputs/real.txt").unwrap();
         assert_eq!(p2(&inp).unwrap(), 0);
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, str::FromStr};
+use std::str::FromStr;
 
 use itertools::Itertools;
 use nom::{
@@ -8,29 +8,26 @@ use nom::{
     sequence::{preceded, tuple},
     IResult,
 };
-use pathfinding::directed::dfs::dfs_reach;
 
-use aoc2022lib::{impl_from_str_from_nom_parser, points::Point3D};
+use aoc2022lib::{
+    collections::FastSet,
+    flood::{flood_fill, Connectivity},
+    impl_from_str_from_nom_parser,
+    points::{Bounds3D, Point3D},
+    AocError,
+};
 
 type DropletCube = Point3D<i8>;
 
-struct DropletBoundaries {
-    x_min: i8,
-    x_max: i8,
-    y_min: i8,
-    y_max: i8,
-    z_min: i8,
-    z_max: i8,
-}
-
-struct Droplet {
-    cubes: HashSet<DropletCube>,
+/// The cubes the lava droplet is made of
+pub struct Droplet {
+    cubes: FastSet<DropletCube>,
 }
 
 impl Droplet {
     fn from_droplet_cubes<I: IntoIterator<Item = DropletCube>>(cubes: I) -> Self {
         Self {
-            cubes: HashSet::from_iter(cubes),
+            cubes: FastSet::from_iter(cubes),
         }
     }
 
@@ -42,18 +39,8 @@ impl Droplet {
         self.cubes.contains(&cube)
     }
 
-    fn boundaries(&self) -> DropletBoundaries {
-        let (x_min, x_max) = self.cubes().map(Point3D::x).minmax().into_option().unwrap();
-        let (y_min, y_max) = self.cubes().map(Point3D::y).minmax().into_option().unwrap();
-        let (z_min, z_max) = self.cubes().map(Point3D::z).minmax().into_option().unwrap();
-        DropletBoundaries {
-            x_min,
-            x_max,
-            y_min,
-            y_max,
-            z_min,
-            z_max,
-        }
+    fn bounds(&self) -> Bounds3D<i8> {
+        Bounds3D::from_points(self.cubes().copied()).expect("at least one cube")
     }
 }
 
@@ -74,9 +61,19 @@ fn droplet(input: &str) -> IResult<&str, Droplet> {
 
 impl_from_str_from_nom_parser!(droplet, Droplet);
 
-pub fn p1(file: &str) -> anyhow::Result<usize> {
+/// The droplet, with room around it to look at its sides without overflowing
+pub fn parse(file: &str) -> aoc2022lib::Result<Droplet> {
     let droplet = Droplet::from_str(file)?;
+    if droplet
+        .cubes()
+        .any(|&Point3D(x, y, z)| [x, y, z].iter().any(|&c| c == i8::MIN || c == i8::MAX))
+    {
+        return Err(AocError::Overflow);
+    }
+    Ok(droplet)
+}
 
+pub fn part1(droplet: &Droplet) -> aoc2022lib::Result<usize> {
     // multiple droplets can have the same point as a potential exposed side (PES),
     // so there will be duplicate values here
     let num_exposed_sides: usize = droplet
@@ -88,28 +85,13 @@ pub fn p1(file: &str) -> anyhow::Result<usize> {
     Ok(num_exposed_sides)
 }
 
-pub fn p2(file: &str) -> anyhow::Result<usize> {
-    let droplet = Droplet::from_str(file)?;
-
-    let boundaries = droplet.boundaries();
-    // sides accessible from outside the droplet
-    let exteriour_sides: HashSet<Point3D<i8>> = dfs_reach(
-        Point3D::<i8>(boundaries.x_min, boundaries.y_min, boundaries.z_min),
-        |air_point: &Point3D<i8>| {
-            air_point
-                .neighbours()
-                .into_iter()
-                // can't go inside droplet
-                .filter(|point| !(droplet.contains(*point)))
-                // limit the searched volume to around the droplet
-                .filter(|Point3D(x, y, z)| {
-                    ((boundaries.x_min - 1)..=(boundaries.x_max + 1)).contains(x)
-                        && ((boundaries.y_min - 1)..=(boundaries.y_max + 1)).contains(y)
-                        && ((boundaries.z_min - 1)..=(boundaries.z_max + 1)).contains(z)
-                })
-        },
-    )
-    .collect();
+pub fn part2(droplet: &Droplet) -> aoc2022lib::Result<usize> {
+    // limit the searched volume to around the droplet
+    let search_space = droplet.bounds().expand(1);
+    // sides accessible from outside the droplet, which can't be gone through
+    let exteriour_sides = flood_fill(search_space.min, Connectivity::Orthogonal, |point| {
+        search_space.contains(point) && !droplet.contains(*point)
+    });
 
     let num_exteriour_exposed_sides = droplet
         .cubes()
@@ -127,11 +109,27 @@ pub fn p2(file: &str) -> anyhow::Result<usize> {
     Ok(num_exteriour_exposed_sides)
 }
 
+pub fn p1(file: &str) -> aoc2022lib::Result<usize> {
+    part1(&parse(file)?)
+}
+
+pub fn p2(file: &str) -> aoc2022lib::Result<usize> {
+    part2(&parse(file)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs::read_to_string;
 
+    #[test]
+    fn bad_cube() {
+        let err = p1("1,1,1\n2,2,x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: failed to parse \"2,2,x\": unexpected input"
+        );
+    }
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
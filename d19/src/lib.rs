@@ -1,12 +1,225 @@
-use std::str::FromStr;
+use aoc2022lib::{
+    impl_from_str_from_nom_parser,
+    math::CheckedFold,
+    parse::n,
+    search::{best_parallel, SearchProblem},
+    AocError,
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::multispace1,
+    combinator::map,
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, tuple},
+    IResult,
+};
 
-use anyhow::{Error, Result};
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Resource {
+    Ore,
+    Clay,
+    Obsidian,
+    Geode,
+}
+
+const RESOURCES: [Resource; 4] = [
+    Resource::Ore,
+    Resource::Clay,
+    Resource::Obsidian,
+    Resource::Geode,
+];
+
+/// One amount for each [`Resource`], in the same order
+type Amounts = [u32; 4];
+
+struct Blueprint {
+    id: u32,
+    /// What a robot collecting each resource costs
+    costs: [Amounts; 4],
+}
+
+// Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot
+// costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+fn blueprint(i: &str) -> IResult<&str, Blueprint> {
+    map(
+        tuple((
+            delimited(tag("Blueprint "), n, tag(":")),
+            preceded(
+                multispace1,
+                delimited(tag("Each ore robot costs "), n, tag(" ore.")),
+            ),
+            preceded(
+                multispace1,
+                delimited(tag("Each clay robot costs "), n, tag(" ore.")),
+            ),
+            preceded(
+                multispace1,
+                delimited(
+                    tag("Each obsidian robot costs "),
+                    separated_pair(n, tag(" ore and "), n),
+                    tag(" clay."),
+                ),
+            ),
+            preceded(
+                multispace1,
+                delimited(
+                    tag("Each geode robot costs "),
+                    separated_pair(n, tag(" ore and "), n),
+                    tag(" obsidian."),
+                ),
+            ),
+        )),
+        |(id, ore, clay, (obsidian_ore, obsidian_clay), (geode_ore, geode_obsidian))| Blueprint {
+            id,
+            costs: [
+                [ore, 0, 0, 0],
+                [clay, 0, 0, 0],
+                [obsidian_ore, obsidian_clay, 0, 0],
+                [geode_ore, 0, geode_obsidian, 0],
+            ],
+        },
+    )(i)
+}
+
+/// The blueprints, in the order they're listed
+pub struct Blueprints(Vec<Blueprint>);
+
+// one blueprint per line, or split over several lines
+fn blueprints(i: &str) -> IResult<&str, Blueprints> {
+    map(separated_list1(multispace1, blueprint), Blueprints)(i)
+}
+
+impl_from_str_from_nom_parser!(blueprints, Blueprints);
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Factory {
+    minutes_left: u32,
+    robots: Amounts,
+    resources: Amounts,
+}
+
+struct GeodeCracking<'a> {
+    blueprint: &'a Blueprint,
+    /// More robots of a kind than this can't be kept busy, as only one robot is built a minute
+    max_useful_robots: Amounts,
+}
+
+impl<'a> GeodeCracking<'a> {
+    fn new(blueprint: &'a Blueprint) -> Self {
+        let mut max_useful_robots = [u32::MAX; 4];
+        for resource in [Resource::Ore, Resource::Clay, Resource::Obsidian] {
+            max_useful_robots[resource as usize] = blueprint
+                .costs
+                .iter()
+                .map(|cost| cost[resource as usize])
+                .max()
+                .unwrap_or_default();
+        }
+        Self {
+            blueprint,
+            max_useful_robots,
+        }
+    }
+
+    /// How long until the robot can be built, if the current robots ever collect enough for it
+    fn minutes_to_afford(&self, factory: &Factory, robot: Resource) -> Option<u32> {
+        let cost = self.blueprint.costs[robot as usize];
+        (0..4)
+            .map(|resource| {
+                let missing = cost[resource].saturating_sub(factory.resources[resource]);
+                match (missing, factory.robots[resource]) {
+                    (0, _) => Some(0),
+                    (_, 0) => None,
+                    (missing, robots) => Some(missing.div_ceil(robots)),
+                }
+            })
+            .try_fold(0, |acc, minutes| Some(acc.max(minutes?)))
+    }
+}
+
+impl SearchProblem for GeodeCracking<'_> {
+    type State = Factory;
+    type Decision = Resource;
+    type Value = u32;
+
+    /// The geodes there'll be in the end even if nothing else is built
+    fn value(&self, factory: &Factory) -> u32 {
+        factory.resources[Resource::Geode as usize]
+            + factory.robots[Resource::Geode as usize] * factory.minutes_left
+    }
+
+    /// Which robot to build next, waiting for it as long as it takes
+    fn moves(&self, factory: &Factory) -> Vec<(Resource, Factory)> {
+        RESOURCES
+            .into_iter()
+            .filter(|&robot| {
+                factory.robots[robot as usize] < self.max_useful_robots[robot as usize]
+            })
+            .filter_map(|robot| {
+                let minutes = self.minutes_to_afford(factory, robot)? + 1;
+                // a robot built in the last minute doesn't collect anything
+                if minutes >= factory.minutes_left {
+                    return None;
+                }
+                let cost = self.blueprint.costs[robot as usize];
+                let mut next = factory.clone();
+                next.minutes_left -= minutes;
+                let collected = factory.robots.into_iter().zip(cost);
+                for (amount, (robots, cost)) in next.resources.iter_mut().zip(collected) {
+                    *amount = *amount + robots * minutes - cost;
+                }
+                next.robots[robot as usize] += 1;
+                Some((robot, next))
+            })
+            .collect()
+    }
+
+    /// As if a geode robot was built every minute from now on
+    fn upper_bound(&self, factory: &Factory) -> Option<u32> {
+        let minutes = factory.minutes_left;
+        Some(self.value(factory) + minutes * minutes.saturating_sub(1) / 2)
+    }
+}
+
+fn max_geodes(blueprint: &Blueprint, minutes: u32) -> u32 {
+    let start = Factory {
+        minutes_left: minutes,
+        robots: [1, 0, 0, 0],
+        resources: [0; 4],
+    };
+    best_parallel(&GeodeCracking::new(blueprint), &start).value
+}
+
+pub fn parse(file: &str) -> aoc2022lib::Result<Blueprints> {
+    Ok(file.parse()?)
+}
 
-pub fn p1(file: &str) -> Result<u32> {
-    todo!()
+pub fn part1(Blueprints(blueprints): &Blueprints) -> aoc2022lib::Result<u32> {
+    blueprints
+        .iter()
+        .map(|blueprint| {
+            blueprint
+                .id
+                .checked_mul(max_geodes(blueprint, 24))
+                .ok_or(AocError::Overflow)
+        })
+        .collect::<aoc2022lib::Result<Vec<_>>>()?
+        .into_iter()
+        .checked_sum()
 }
-pub fn p2(_file: &str) -> Result<u32> {
-    todo!()
+pub fn part2(Blueprints(blueprints): &Blueprints) -> aoc2022lib::Result<u32> {
+    blueprints
+        .iter()
+        .take(3)
+        .map(|blueprint| max_geodes(blueprint, 32))
+        .checked_product()
+}
+
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
@@ -20,19 +233,18 @@ mod tests {
         assert_eq!(p1(&inp).unwrap(), 33);
     }
     #[test]
-    #[ignore]
+    #[ignore = "answer not recorded yet"]
     fn real_p1() {
         let inp = read_to_string("inputs/real.txt").unwrap();
         assert_eq!(p1(&inp).unwrap(), 0);
     }
     #[test]
-    #[ignore]
     fn test_p2() {
         let inp = read_to_string("inputs/test.txt").unwrap();
-        assert_eq!(p2(&inp).unwrap(), 0);
+        assert_eq!(p2(&inp).unwrap(), 56 * 62);
     }
     #[test]
-    #[ignore]
+    #[ignore = "answer not recorded yet"]
     fn real_p2() {
         let inp = read_to_string("inputs/real.txt").unwrap();
         assert_eq!(p2(&inp).unwrap(), 0);
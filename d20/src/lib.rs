@@ -1,5 +1,8 @@
-use anyhow::Context;
-use std::str::FromStr;
+use aoc2022lib::{
+    math::{CheckedFold, ModInt},
+    parse::Parse,
+    AocError,
+};
 
 struct Number {
     value: i64,
@@ -13,88 +16,94 @@ impl Number {
 }
 
 trait Mix {
-    fn mix(&mut self) -> anyhow::Result<&mut Self>;
+    fn mix(&mut self) -> aoc2022lib::Result<&mut Self>;
 }
 
 impl Mix for Vec<Number> {
-    fn mix(&mut self) -> anyhow::Result<&mut Self> {
+    fn mix(&mut self) -> aoc2022lib::Result<&mut Self> {
         let len = self.len();
+        // a single number has nowhere to move to
+        if len <= 1 {
+            return Ok(self);
+        }
+        // the moved number isn't in the list while looking for its new place
+        let modulus = u64::try_from(len - 1)?;
         for order in 0..len {
             let position = self
                 .iter()
                 .position(|number| number.order == order)
                 .expect("I just put you there");
             let number = self.remove(position);
-            let new_position = {
-                let position: i64 = position.try_into()?;
-                let len: i64 = len.try_into()?;
-                let new_position = (position + number.value).rem_euclid(len - 1);
-                new_position.try_into()?
-            };
-            self.insert(new_position, number);
+            let new_position = ModInt::new(u64::try_from(position)?, modulus)
+                + ModInt::from_signed(number.value, modulus);
+            self.insert(usize::try_from(new_position.value())?, number);
         }
         Ok(self)
     }
 }
 
-pub fn p1(file: &str) -> anyhow::Result<i64> {
-    let mut numbers: Vec<Number> = file
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .enumerate()
-        .map(|(idx, n)| Number::new(n, idx))
-        .collect();
+/// The numbers, in their original order
+pub struct EncryptedFile(Vec<i64>);
 
-    numbers.mix()?;
+pub fn parse(file: &str) -> aoc2022lib::Result<EncryptedFile> {
+    Ok(EncryptedFile(Vec::parse(file)?))
+}
+
+fn numbers(
+    EncryptedFile(numbers): &EncryptedFile,
+    decryption_key: i64,
+) -> aoc2022lib::Result<Vec<Number>> {
+    numbers
+        .iter()
+        .enumerate()
+        .map(|(idx, n)| {
+            let value = n.checked_mul(decryption_key).ok_or(AocError::Overflow)?;
+            Ok(Number::new(value, idx))
+        })
+        .collect()
+}
 
+fn grove_coordinates(numbers: &[Number]) -> aoc2022lib::Result<i64> {
     let idx_of_zero = numbers
         .iter()
         .position(|number| number.value == 0)
-        .context("No 0 in list")?;
+        .ok_or_else(|| AocError::missing_input("No 0 in list"))?;
 
-    let res = [1000, 2000, 3000]
+    [1000, 2000, 3000]
         .into_iter()
         .map(|position| (idx_of_zero + position) % numbers.len())
         .map(|position| &numbers[position])
         .map(|number| number.value)
-        .sum();
+        .checked_sum()
+}
+
+pub fn part1(file: &EncryptedFile) -> aoc2022lib::Result<i64> {
+    let mut numbers = numbers(file, 1)?;
+
+    numbers.mix()?;
 
-    Ok(res)
+    grove_coordinates(&numbers)
 }
 
-pub fn p2(file: &str) -> anyhow::Result<i64> {
+pub fn part2(file: &EncryptedFile) -> aoc2022lib::Result<i64> {
     let num_mixes = 10;
     let decryption_key: i64 = 811_589_153;
 
-    let mut numbers: Vec<Number> = file
-        .lines()
-        .map(i64::from_str)
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .map(|n| decryption_key * n)
-        .enumerate()
-        .map(|(idx, n)| Number::new(n, idx))
-        .collect();
+    let mut numbers = numbers(file, decryption_key)?;
 
     for _ in 0..num_mixes {
         numbers.mix()?;
     }
 
-    let idx_of_zero = numbers
-        .iter()
-        .position(|number| number.value == 0)
-        .context("No 0 in list")?;
+    grove_coordinates(&numbers)
+}
 
-    let res = [1000, 2000, 3000]
-        .into_iter()
-        .map(|position| (idx_of_zero + position) % numbers.len())
-        .map(|position| &numbers[position])
-        .map(|number| number.value)
-        .sum();
+pub fn p1(file: &str) -> aoc2022lib::Result<i64> {
+    part1(&parse(file)?)
+}
 
-    Ok(res)
+pub fn p2(file: &str) -> aoc2022lib::Result<i64> {
+    part2(&parse(file)?)
 }
 
 #[cfg(test)]
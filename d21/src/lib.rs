@@ -1,17 +1,17 @@
-use std::{
-    collections::HashMap,
-    ops::{Add, Div, Mul, Sub},
-    str::FromStr,
-};
+use std::str::FromStr;
 
-use anyhow::{bail, Context};
-use aoc2022lib::impl_from_str_for_obj_with_lieftimes_from_nom_parser;
+use aoc2022lib::{
+    collections::FastMap,
+    impl_parse_from_nom_parser,
+    parse::{Parse, ParseError},
+    AocError,
+};
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take},
+    bytes::complete::take,
     character::complete::{char, u64},
     combinator::{map, map_res},
-    sequence::{preceded, separated_pair, tuple},
+    sequence::{preceded, tuple},
     IResult,
 };
 
@@ -19,7 +19,7 @@ type Number = u64;
 
 type Name<'a> = &'a str;
 
-fn name(i: &str) -> IResult<&str, Name> {
+fn name(i: &str) -> IResult<&str, Name<'_>> {
     take(4usize)(i)
 }
 
@@ -32,7 +32,7 @@ enum Operation {
 }
 
 impl FromStr for Operation {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -40,7 +40,7 @@ impl FromStr for Operation {
             "-" => Ok(Self::Sub),
             "*" => Ok(Self::Mul),
             "/" => Ok(Self::Div),
-            s => bail!("Invalid operation: {s}"),
+            s => Err(ParseError::new(s, "invalid operation")),
         }
     }
 }
@@ -49,20 +49,15 @@ fn operation(i: &str) -> IResult<&str, Operation> {
     map_res(take(1usize), str::parse)(i)
 }
 
-trait ApplyOperation<T> {
-    fn apply_operation(self, operation: Operation, other: T) -> T;
-}
-
-impl<T> ApplyOperation<T> for T
-where
-    T: Add<Output = T> + Sub<Output = T> + Div<Output = T> + Mul<Output = T>,
-{
-    fn apply_operation(self, operation: Operation, other: T) -> T {
-        match operation {
-            Operation::Add => self + other,
-            Operation::Sub => self - other,
-            Operation::Mul => self * other,
-            Operation::Div => self / other,
+impl Operation {
+    fn apply(self, lhs: Number, rhs: Number) -> aoc2022lib::Result<Number> {
+        match self {
+            Operation::Add => lhs.checked_add(rhs).ok_or(AocError::Overflow),
+            Operation::Sub => lhs.checked_sub(rhs).ok_or(AocError::Overflow),
+            Operation::Mul => lhs.checked_mul(rhs).ok_or(AocError::Overflow),
+            Operation::Div => lhs
+                .checked_div(rhs)
+                .ok_or_else(|| AocError::invalid_input("a monkey divides by 0")),
         }
     }
 }
@@ -76,7 +71,7 @@ enum Job<'a> {
     },
 }
 
-fn job(i: &str) -> IResult<&str, Job> {
+fn job(i: &str) -> IResult<&str, Job<'_>> {
     alt((
         map(u64, Job::Number),
         map(
@@ -94,76 +89,79 @@ fn job(i: &str) -> IResult<&str, Job> {
     ))(i)
 }
 
-struct Monkey<'a> {
-    name: Name<'a>,
-    job: Job<'a>,
-}
+impl_parse_from_nom_parser!(job, Job<'a>);
 
-fn monkey(i: &str) -> IResult<&str, Monkey> {
-    map(separated_pair(name, tag(": "), job), |(name, job)| Monkey {
-        name,
-        job,
-    })(i)
+/// Each monkey's job, by name
+pub struct Monkeys<'a> {
+    monkeys: FastMap<Name<'a>, Job<'a>>,
 }
 
-impl_from_str_for_obj_with_lieftimes_from_nom_parser!(monkey, Monkey);
-
-struct Monkeys<'a> {
-    monkeys: HashMap<Name<'a>, Job<'a>>,
+impl<'a> Parse<'a> for Monkeys<'a> {
+    fn parse(s: &'a str) -> Result<Self, aoc2022lib::parse::ParseError> {
+        Ok(Self {
+            monkeys: FastMap::parse(s)?,
+        })
+    }
 }
 
-impl<'input, 'output> TryFrom<&'input str> for Monkeys<'output>
-where
-    'input: 'output,
-{
-    type Error = nom::error::Error<String>;
-
-    fn try_from(s: &'input str) -> Result<Self, Self::Error> {
-        let mut monkeys: HashMap<Name, Job> = HashMap::with_capacity(s.lines().count());
-
-        for line in s.lines() {
-            let monkey = Monkey::try_from(line)?;
-            monkeys.insert(monkey.name, monkey.job);
-        }
-
-        Ok(Self { monkeys })
+impl Monkeys<'_> {
+    fn number(&self, name: &str) -> aoc2022lib::Result<Number> {
+        self.number_waited_on(name, 0)
     }
-}
 
-impl<'a> Monkeys<'a> {
-    fn number(&self, name: &str) -> Option<Number> {
-        let job = self.monkeys.get(name)?;
+    /// `num_waiting` monkeys are waiting on this one, more than there are means they're going
+    /// in circles
+    fn number_waited_on(&self, name: &str, num_waiting: usize) -> aoc2022lib::Result<Number> {
+        if num_waiting > self.monkeys.len() {
+            return Err(AocError::invalid_input("the monkeys wait on each other"));
+        }
+        let job = self
+            .monkeys
+            .get(name)
+            .ok_or_else(|| AocError::missing_input(format!("No {name} in list")))?;
 
         match *job {
-            Job::Number(num) => Some(num),
+            Job::Number(num) => Ok(num),
             Job::Calculate {
                 monkey_1st,
                 operation,
                 monkey_2nd,
             } => {
-                let num_1st = self.number(monkey_1st)?;
-                let num_2nd = self.number(monkey_2nd)?;
-                let num = num_1st.apply_operation(operation, num_2nd);
-                Some(num)
+                let num_1st = self.number_waited_on(monkey_1st, num_waiting + 1)?;
+                let num_2nd = self.number_waited_on(monkey_2nd, num_waiting + 1)?;
+                operation.apply(num_1st, num_2nd)
             }
         }
     }
 }
 
-pub fn p1(file: &str) -> anyhow::Result<Number> {
-    let monkeys = Monkeys::try_from(file)?;
-    let number = monkeys.number("root").context("No root in list")?;
-    Ok(number)
+pub fn parse(file: &str) -> aoc2022lib::Result<Monkeys<'_>> {
+    Ok(Monkeys::parse(file)?)
+}
+
+pub fn part1(monkeys: &Monkeys) -> aoc2022lib::Result<Number> {
+    monkeys.number("root")
 }
-pub fn p2(_file: &str) -> anyhow::Result<u32> {
+pub fn part2(_monkeys: &Monkeys) -> aoc2022lib::Result<u32> {
     todo!()
 }
 
+pub fn p1(file: &str) -> aoc2022lib::Result<Number> {
+    part1(&parse(file)?)
+}
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs::read_to_string;
 
+    #[test]
+    fn duplicate_monkey() {
+        assert!(matches!(p1("root: 5\nroot: 6"), Err(AocError::Parse(_))));
+    }
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
@@ -175,13 +173,13 @@ mod tests {
         assert_eq!(p1(&inp).unwrap(), 31_017_034_894_002);
     }
     #[test]
-    #[ignore]
+    #[ignore = "not solved yet"]
     fn test_p2() {
         let inp = read_to_string("inputs/test.txt").unwrap();
         assert_eq!(p2(&inp).unwrap(), 0);
     }
     #[test]
-    #[ignore]
+    #[ignore = "not solved yet"]
     fn real_p2() {
         let inp = read_to_string("inputs/real.txt").unwrap();
         assert_eq!(p2(&inp).unwrap(), 0);
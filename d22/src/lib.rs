@@ -1,36 +1,117 @@
-use std::str::FromStr;
+use aoc2022lib::{impl_from_str_from_nom_parser, parse::n, AocError};
+use nom::{
+    branch::alt,
+    character::complete::char,
+    combinator::{map, value},
+    multi::many1,
+    IResult,
+};
 
-pub fn p1(file: &str) -> anyhow::Result<u32> {
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Step {
+    Forward(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+struct Path(Vec<Step>);
+
+// 10R5L5
+fn path(i: &str) -> IResult<&str, Path> {
+    let step = alt((
+        map(n, Step::Forward),
+        value(Step::TurnLeft, char('L')),
+        value(Step::TurnRight, char('R')),
+    ));
+    map(many1(step), Path)(i)
+}
+
+impl_from_str_from_nom_parser!(path, Path);
+
+/// The board, a row per line with the tiles off it as spaces, and the path to follow on it
+#[allow(dead_code, reason = "read by part 1 and 2 once they're solved")]
+pub struct Notes<'a> {
+    board: Vec<&'a str>,
+    path: Path,
+}
+
+pub fn parse(file: &str) -> aoc2022lib::Result<Notes<'_>> {
+    let (board, path) = file
+        .split_once("\n\n")
+        .ok_or_else(|| AocError::missing_input("No path after the board"))?;
+    let board: Vec<&str> = board.lines().collect();
+    if let Some((idx, row)) = (1..)
+        .zip(&board)
+        .find(|(_, row)| !row.chars().all(|tile| matches!(tile, ' ' | '.' | '#')))
+    {
+        return Err(AocError::invalid_input(format!(
+            "line {idx}: {row:?} isn't a row of tiles"
+        )));
+    }
+    Ok(Notes {
+        path: path
+            .trim_end()
+            .parse()
+            .map_err(|err: aoc2022lib::parse::ParseError| err.on_line(board.len() + 2))?,
+        board,
+    })
+}
+
+pub fn part1(_notes: &Notes) -> aoc2022lib::Result<u32> {
     todo!()
 }
-pub fn p2(_file: &str) -> anyhow::Result<u32> {
+pub fn part2(_notes: &Notes) -> aoc2022lib::Result<u32> {
     todo!()
 }
 
+pub fn p1(file: &str) -> aoc2022lib::Result<u32> {
+    part1(&parse(file)?)
+}
+pub fn p2(file: &str) -> aoc2022lib::Result<u32> {
+    part2(&parse(file)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs::read_to_string;
 
+    #[test]
+    fn parse_path() {
+        use Step::{Forward, TurnLeft, TurnRight};
+        assert_eq!(
+            "10R5L0".parse(),
+            Ok(Path(vec![
+                Forward(10),
+                TurnRight,
+                Forward(5),
+                TurnLeft,
+                Forward(0)
+            ]))
+        );
+        assert!("10X5".parse::<Path>().is_err());
+    }
+
     #[test]
     fn test_p1() {
         let inp = read_to_string("inputs/test.txt").unwrap();
         assert_eq!(p1(&inp).unwrap(), 6032);
     }
     #[test]
-    #[ignore]
+    #[ignore = "not solved yet"]
     fn real_p1() {
         let inp = read_to_string("inputs/real.txt").unwrap();
         assert_eq!(p1(&inp).unwrap(), 0);
     }
     #[test]
-    #[ignore]
+    #[ignore = "not solved yet"]
     fn test_p2() {
         let inp = read_to_string("inputs/test.txt").unwrap();
         assert_eq!(p2(&inp).unwrap(), 0);
     }
     #[test]
-    #[ignore]
+    #[ignore = "not solved yet"]
     fn real_p2() {
         let inp = read_to_string("inputs/real.txt").unwrap();
         assert_eq!(p2(&inp).unwrap(), 0);
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|file: &str| {
+    let _ = d02::p1(file);
+    let _ = d02::p2(file);
+});
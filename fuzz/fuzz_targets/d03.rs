@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|file: &str| {
+    let _ = d03::p1(file);
+    let _ = d03::p2(file);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|file: &str| {
+    let _ = d10::p1(file);
+    let _ = d10::p2(file);
+    let _ = d10::p2_decoded(file);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// the parts aren't solved yet
+fuzz_target!(|file: &str| {
+    let _ = d22::parse(file);
+});
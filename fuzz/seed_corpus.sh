@@ -0,0 +1,15 @@
+#!/bin/sh
+# Seeds every fuzz target's corpus with the inputs of its day, e.g.:
+#   ./fuzz/seed_corpus.sh && cargo +nightly fuzz run d14
+set -eu
+
+fuzz_dir=$(dirname "$0")
+
+for target in "$fuzz_dir"/fuzz_targets/*.rs; do
+    day=$(basename "$target" .rs)
+    mkdir -p "$fuzz_dir/corpus/$day"
+    for input in "$fuzz_dir/../$day"/inputs/*.txt; do
+        [ -e "$input" ] || continue
+        cp "$input" "$fuzz_dir/corpus/$day/$(basename "$input")"
+    done
+done
@@ -0,0 +1,280 @@
+use std::{fmt::Write as _, time::Duration};
+
+use crate::{
+    history::Run,
+    record::{Record, Status},
+    summary::{columns, duration},
+};
+
+/// How long a part took in two runs on the same input, parsing included
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Delta {
+    pub day: u8,
+    pub part: u8,
+    pub baseline_ns: u64,
+    pub current_ns: u64,
+}
+
+impl Delta {
+    /// How much longer the current run took, in percent of the baseline, negative if it was faster
+    #[allow(clippy::cast_precision_loss)]
+    pub fn change_percent(&self) -> f64 {
+        (self.current_ns as f64 - self.baseline_ns as f64) / self.baseline_ns.max(1) as f64 * 100.
+    }
+}
+
+fn total_ns(record: &Record) -> Option<u64> {
+    if record.status != Status::Ok {
+        return None;
+    }
+    Some(record.parse_ns?.saturating_add(record.solve_ns?))
+}
+
+/// The parts solved in both runs on the same input, in the current run's order
+pub fn deltas(baseline: &Run, current: &Run) -> Vec<Delta> {
+    current
+        .records
+        .iter()
+        .filter_map(|record| {
+            let before = baseline.records.iter().find(|before| {
+                (before.day, before.part, &before.input_hash)
+                    == (record.day, record.part, &record.input_hash)
+            })?;
+            Some(Delta {
+                day: record.day,
+                part: record.part,
+                baseline_ns: total_ns(before)?,
+                current_ns: total_ns(record)?,
+            })
+        })
+        .collect()
+}
+
+/// The latest of the `earlier` runs to compare `current` to, at `commit` if given. It has to
+/// have been solved the same way, and have timings that can be trusted
+pub fn baseline<'a>(earlier: &'a [Run], current: &Run, commit: Option<&str>) -> Option<&'a Run> {
+    earlier.iter().rev().find(|run| {
+        run.comparable_to(current)
+            && run.has_reliable_timings()
+            && commit.is_none_or(|commit| {
+                run.commit
+                    .as_deref()
+                    .is_some_and(|run_commit| run_commit.starts_with(commit))
+            })
+    })
+}
+
+/// When a part counts as having regressed
+#[derive(Clone, Copy, Debug)]
+pub struct Thresholds {
+    /// By how many percent a part may take longer than it did before
+    pub max_regression_percent: f64,
+    /// Parts quicker than this in both runs are too noisy to tell, they never regress
+    pub min_duration: Duration,
+}
+
+impl Thresholds {
+    fn regressed(&self, delta: &Delta) -> bool {
+        let min_ns = u64::try_from(self.min_duration.as_nanos()).unwrap_or(u64::MAX);
+        delta.change_percent() > self.max_regression_percent
+            && delta.baseline_ns.max(delta.current_ns) >= min_ns
+    }
+}
+
+/// A table of how each part's time changed from `baseline` to `current`, and whether any
+/// of them regressed beyond `thresholds`
+pub fn report(baseline: &Run, current: &Run, thresholds: &Thresholds) -> (String, bool) {
+    let deltas = deltas(baseline, current);
+    let rows: Vec<[String; 5]> = deltas
+        .iter()
+        .map(|delta| {
+            [
+                delta.day.to_string(),
+                delta.part.to_string(),
+                duration(Some(delta.baseline_ns)),
+                duration(Some(delta.current_ns)),
+                format!(
+                    "{:+.1}%{}",
+                    delta.change_percent(),
+                    if thresholds.regressed(delta) {
+                        "  regressed"
+                    } else {
+                        ""
+                    }
+                ),
+            ]
+        })
+        .collect();
+
+    let mut res = format!("{} -> {}\n\n", baseline.label(), current.label());
+    res.push_str(&columns(
+        ["day", "part", "before", "after", "change"],
+        [true, true, true, true, false],
+        &rows,
+    ));
+    let num_regressed = deltas
+        .iter()
+        .filter(|delta| thresholds.regressed(delta))
+        .count();
+    writeln!(
+        res,
+        "\n{num_regressed} of {} parts regressed by more than {}%",
+        deltas.len(),
+        thresholds.max_regression_percent
+    )
+    .expect("writing to a string");
+    let num_skipped = current.records.len() - deltas.len();
+    if num_skipped > 0 {
+        writeln!(
+            res,
+            "{num_skipped} parts weren't compared, they failed or their input changed"
+        )
+        .expect("writing to a string");
+    }
+    (res, num_regressed > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        days::{Failure, Solved},
+        history::Setup,
+    };
+
+    fn run(commit: &str, solve_times_ms: [u64; 3]) -> Run {
+        let record = |day, part, solve_time_ms, input_hash| {
+            let solved = Solved {
+                part,
+                answer: Ok("42".to_owned()),
+                parse_time: Some(Duration::ZERO),
+                solve_time: Some(Duration::from_millis(solve_time_ms)),
+            };
+            Record::new(day, solved, input_hash)
+        };
+        let failed = Solved {
+            part: 1,
+            answer: Err(Failure::Panic("not yet implemented".to_owned())),
+            parse_time: Some(Duration::ZERO),
+            solve_time: Some(Duration::ZERO),
+        };
+        Run {
+            commit: Some(commit.to_owned()),
+            dirty: false,
+            timestamp: 0,
+            setup: Some(Setup {
+                parallel: true,
+                isolated: false,
+                threads: 8,
+            }),
+            records: vec![
+                record(15, 1, solve_times_ms[0], "a"),
+                record(15, 2, solve_times_ms[1], "a"),
+                record(17, 1, solve_times_ms[2], commit),
+                Record::new(22, failed, "b"),
+            ],
+        }
+    }
+
+    #[test]
+    fn regressions() {
+        let thresholds = Thresholds {
+            max_regression_percent: 10.,
+            min_duration: Duration::from_millis(1),
+        };
+        let baseline = run("0123456789abcdef", [100, 10, 10]);
+        let (_, regressed) = report(&baseline, &run("1", [105, 5, 20]), &thresholds);
+        assert!(!regressed);
+
+        let current = run("fedcba9876543210", [120, 10, 10]);
+        assert_eq!(deltas(&baseline, &current).len(), 2);
+        let (report, regressed) = report(&baseline, &current, &thresholds);
+        assert!(regressed);
+        assert_eq!(
+            report,
+            "\
+0123456789 -> fedcba9876
+
+day  part    before     after  change
+ 15     1  100.00ms  120.00ms  +20.0%  regressed
+ 15     2   10.00ms   10.00ms  +0.0%
+
+1 of 2 parts regressed by more than 10%
+2 parts weren't compared, they failed or their input changed
+"
+        );
+    }
+
+    #[test]
+    fn baselines() {
+        let setup = |run: &Run| run.setup.unwrap();
+        let current = run("current", [100, 10, 10]);
+        let mut earlier = vec![
+            run("0123", [100, 10, 10]),
+            run("4567", [100, 10, 10]),
+            run("89ab", [100, 10, 10]),
+        ];
+        assert_eq!(
+            baseline(&earlier, &current, None)
+                .unwrap()
+                .commit
+                .as_deref(),
+            Some("89ab")
+        );
+        assert_eq!(
+            baseline(&earlier, &current, Some("45"))
+                .unwrap()
+                .commit
+                .as_deref(),
+            Some("4567")
+        );
+
+        // solved one day after another, with fewer threads, and before runs had a setup
+        earlier[2].setup = Some(Setup {
+            parallel: false,
+            ..setup(&current)
+        });
+        earlier[1].setup = Some(Setup {
+            threads: 4,
+            ..setup(&current)
+        });
+        earlier[0].setup = None;
+        assert!(baseline(&earlier, &current, None).is_none());
+
+        // a part that was given up on kept running in the background, unless it was killed
+        earlier[1].setup = current.setup;
+        earlier[1].records[0].status = Status::Timeout;
+        assert!(baseline(&earlier, &current, None).is_none());
+        let isolated = Setup {
+            isolated: true,
+            ..setup(&current)
+        };
+        earlier[1].setup = Some(isolated);
+        assert!(baseline(&earlier, &current, None).is_none());
+        let mut current = current;
+        current.setup = Some(isolated);
+        assert_eq!(
+            baseline(&earlier, &current, None)
+                .unwrap()
+                .commit
+                .as_deref(),
+            Some("4567")
+        );
+    }
+
+    #[test]
+    fn noise() {
+        let delta = Delta {
+            day: 1,
+            part: 1,
+            baseline_ns: 1_000,
+            current_ns: 3_000,
+        };
+        assert!((delta.change_percent() - 200.).abs() < f64::EPSILON);
+        let thresholds = Thresholds {
+            max_regression_percent: 10.,
+            min_duration: Duration::from_millis(1),
+        };
+        assert!(!thresholds.regressed(&delta));
+    }
+}
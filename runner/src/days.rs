@@ -0,0 +1,208 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+    time::{Duration, Instant},
+};
+
+/// Why a part has no answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The solution returned an error
+    Error(String),
+    /// The solution panicked, with this message
+    Panic(String),
+    /// The solution was given up on after taking this long
+    Timeout(Duration),
+    /// The process solving it died without saying how it went, e.g. out of memory
+    Crashed(String),
+}
+
+/// How solving a part of a day went
+#[derive(Clone, Debug)]
+pub struct Solved {
+    pub part: u8,
+    pub answer: Result<String, Failure>,
+    /// `None` if parsing was given up on
+    pub parse_time: Option<Duration>,
+    /// `None` if the input couldn't be parsed, so the part never got solved
+    pub solve_time: Option<Duration>,
+}
+
+/// A day's solution, parsing the input once and solving the parts asked for from that,
+/// handing over each part as soon as it's solved
+pub struct Day {
+    pub num: u8,
+    pub solve: fn(input: &str, parts: &[u8], on_solved: &mut dyn FnMut(Solved)),
+}
+
+impl Day {
+    pub fn get(num: u8) -> Option<&'static Day> {
+        DAYS.iter().find(|day| day.num == num)
+    }
+}
+
+pub const PARTS: [u8; 2] = [1, 2];
+
+thread_local! {
+    /// Whether this thread is running a solution, inside [`catch`]
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Keeps the solutions' panics from being printed, they end up in the records. The runner's own
+/// still go to the hook that was there before
+fn silence_solution_panics() {
+    static SILENCED: Once = Once::new();
+    SILENCED.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // rayon's threads only ever run solutions, which are parallel themselves
+            if !SOLVING.get() && rayon::current_thread_index().is_none() {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// `f`'s result, or why there isn't one, panics included
+fn catch<T>(f: impl FnOnce() -> aoc2022lib::Result<T>) -> Result<T, Failure> {
+    silence_solution_panics();
+    SOLVING.set(true);
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    SOLVING.set(false);
+    match res {
+        Ok(Ok(res)) => Ok(res),
+        Ok(Err(err)) => Err(Failure::Error(err.to_string())),
+        Err(payload) => Err(Failure::Panic(panic_message(payload.as_ref()))),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked without a message".to_owned()
+    }
+}
+
+/// A [`Day`] for the crate `$krate`, solving the parts with its `part1` and `part2`
+/// unless told otherwise
+macro_rules! day {
+    ($num:literal, $krate:ident) => {
+        day!($num, $krate, $krate::part1, $krate::part2)
+    };
+    ($num:literal, $krate:ident, $part1:expr, $part2:expr) => {
+        Day {
+            num: $num,
+            solve: |input, parts, on_solved| {
+                let start = Instant::now();
+                let model = catch(|| $krate::parse(input));
+                let parse_time = Some(start.elapsed());
+                for &part in parts {
+                    let model = match &model {
+                        Ok(model) => model,
+                        Err(failure) => {
+                            on_solved(Solved {
+                                part,
+                                answer: Err(failure.clone()),
+                                parse_time,
+                                solve_time: None,
+                            });
+                            continue;
+                        }
+                    };
+                    let start = Instant::now();
+                    let answer = match part {
+                        1 => catch(|| $part1(model).map(|answer| answer.to_string())),
+                        2 => catch(|| $part2(model).map(|answer| answer.to_string())),
+                        _ => Err(Failure::Error(format!("there's no part {part}"))),
+                    };
+                    on_solved(Solved {
+                        part,
+                        answer,
+                        parse_time,
+                        solve_time: Some(start.elapsed()),
+                    });
+                }
+            },
+        }
+    };
+}
+
+/// Every day there's a solution for, with the parameters the puzzles ask for
+pub const DAYS: &[Day] = &[
+    day!(1, d01),
+    day!(2, d02),
+    day!(3, d03),
+    day!(4, d04),
+    day!(5, d05),
+    day!(6, d06),
+    day!(7, d07),
+    day!(8, d08),
+    day!(9, d09),
+    day!(10, d10, d10::part1, d10::part2_decoded),
+    day!(11, d11, |monkeys| d11::part1(monkeys, 20), |monkeys| {
+        d11::part2(monkeys, 10_000)
+    }),
+    day!(12, d12),
+    day!(13, d13),
+    day!(14, d14),
+    day!(
+        15,
+        d15,
+        |sensors| d15::part1(sensors, 2_000_000),
+        |sensors| d15::part2(sensors, 4_000_000)
+    ),
+    day!(17, d17),
+    day!(18, d18),
+    day!(19, d19),
+    day!(20, d20),
+    day!(21, d21),
+    day!(22, d22),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(day: u8, input: &str, parts: &[u8]) -> Vec<Solved> {
+        let mut solved = Vec::new();
+        (Day::get(day).unwrap().solve)(input, parts, &mut |part| solved.push(part));
+        solved
+    }
+
+    #[test]
+    fn parses_once_and_solves_each_part() {
+        let solved = solve(6, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", &PARTS);
+        let answers = solved
+            .into_iter()
+            .map(|solved| solved.answer)
+            .collect::<Vec<_>>();
+        assert_eq!(answers, [Ok("7".to_owned()), Ok("19".to_owned())]);
+    }
+
+    #[test]
+    fn only_solutions_panic_silently() {
+        let failure = catch::<()>(|| panic!("in a solution"));
+        assert_eq!(failure, Err(Failure::Panic("in a solution".to_owned())));
+        assert!(!SOLVING.get());
+    }
+
+    #[test]
+    fn failures() {
+        let solved = solve(22, "..\n\n1R", &[2]);
+        assert_eq!(
+            solved[0].answer,
+            Err(Failure::Panic("not yet implemented".to_owned()))
+        );
+
+        let solved = solve(4, "1-2,3", &PARTS);
+        assert!(solved
+            .iter()
+            .all(|solved| matches!(solved.answer, Err(Failure::Error(_)))
+                && solved.solve_time.is_none()));
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::record::{Record, Status};
+
+/// How a run's parts were solved, only the timings of runs solved the same way compare
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Setup {
+    /// Whether the days were solved in parallel, as `all` does, rather than one after another
+    pub parallel: bool,
+    /// Whether each part got a child process of its own, see `run --isolate`
+    pub isolated: bool,
+    /// How many threads the solutions had to run in parallel on
+    pub threads: usize,
+}
+
+impl Setup {
+    pub fn new(parallel: bool, isolated: bool) -> Self {
+        Self {
+            parallel,
+            isolated,
+            threads: rayon::current_num_threads(),
+        }
+    }
+}
+
+/// One `run` or `all`, a line of the history file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Run {
+    /// The commit the workspace was at, if it's a git repository
+    pub commit: Option<String>,
+    /// Whether there were uncommitted changes to tracked files
+    pub dirty: bool,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// `None` for the runs recorded before it was
+    #[serde(default)]
+    pub setup: Option<Setup>,
+    pub records: Vec<Record>,
+}
+
+fn git(workspace: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(workspace)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+impl Run {
+    /// `records`, as solved from the workspace as it is now
+    pub fn new(workspace: &Path, setup: Setup, records: Vec<Record>) -> Self {
+        Self {
+            commit: git(workspace, &["rev-parse", "HEAD"]),
+            dirty: git(
+                workspace,
+                &["status", "--porcelain", "--untracked-files=no"],
+            )
+            .is_some_and(|changes| !changes.is_empty()),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            setup: Some(setup),
+            records,
+        }
+    }
+
+    /// Whether `other` was solved the same way, so that their timings compare
+    pub fn comparable_to(&self, other: &Run) -> bool {
+        self.setup.is_some() && self.setup == other.setup
+    }
+
+    /// Whether the timings can be trusted. Solved in process, the parts given up on keep
+    /// running and slow down the ones solved after them
+    pub fn has_reliable_timings(&self) -> bool {
+        self.setup.is_some_and(|setup| setup.isolated)
+            || self
+                .records
+                .iter()
+                .all(|record| record.status != Status::Timeout)
+    }
+
+    /// The commit shortened, marked with a `+` if there were changes on top of it
+    pub fn label(&self) -> String {
+        let commit = self.commit.as_deref().map_or("unknown commit", |commit| {
+            commit.get(..10).unwrap_or(commit)
+        });
+        if self.dirty {
+            format!("{commit}+")
+        } else {
+            commit.to_owned()
+        }
+    }
+}
+
+pub fn append(path: &Path, run: &Run) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(run)?)
+}
+
+/// Every run in the history file at `path`, oldest first. No runs if there's no file yet
+pub fn load(path: &Path) -> io::Result<Vec<Run>> {
+    let history = match fs::read_to_string(path) {
+        Ok(history) => history,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    history
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} line {}: {err}", path.display(), idx + 1),
+                )
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,189 @@
+use std::{
+    env,
+    io::Read,
+    path::Path,
+    process::{Command, Stdio},
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    days::{Day, Failure, Solved},
+    record::Record,
+};
+
+/// How often a child process gets checked on
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+fn given_up(part: u8, failure: Failure) -> Solved {
+    Solved {
+        part,
+        answer: Err(failure),
+        parse_time: None,
+        solve_time: None,
+    }
+}
+
+/// Solves `parts` of `day` on a thread of its own, giving up on a part once it's taken longer
+/// than `deadline`, the first part's parsing included. A thread can't be stopped, so the one
+/// given up on keeps going until the process exits, and the parts after it get a new one
+pub fn solve_in_thread(
+    day: &'static Day,
+    input: &Arc<str>,
+    parts: &[u8],
+    deadline: Duration,
+) -> Vec<Solved> {
+    let mut solved = Vec::new();
+    let mut remaining = parts;
+    while !remaining.is_empty() {
+        let (sender, receiver) = mpsc::channel();
+        let input = Arc::clone(input);
+        let parts = remaining.to_vec();
+        thread::spawn(move || {
+            (day.solve)(&input, &parts, &mut |part| {
+                // nobody's listening any more if the part took too long
+                let _ = sender.send(part);
+            });
+        });
+
+        while let Some((&part, rest)) = remaining.split_first() {
+            remaining = rest;
+            match receiver.recv_timeout(deadline) {
+                Ok(part) => solved.push(part),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    solved.push(given_up(part, Failure::Timeout(deadline)));
+                    break;
+                }
+                // `solve` catches the solutions' panics, so this is the runner's own
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    solved.push(given_up(
+                        part,
+                        Failure::Panic("the runner panicked".to_owned()),
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+    solved
+}
+
+/// Solves `part` of `day` in a child process, the `solve` command, killing it if it takes
+/// longer than `deadline`. The child is limited to `memory_limit_mb` of address space if given
+pub fn solve_in_child(
+    day: u8,
+    part: u8,
+    input_path: &Path,
+    input_hash: &str,
+    deadline: Duration,
+    memory_limit_mb: Option<u64>,
+) -> Record {
+    let failed = |failure| Record::new(day, given_up(part, failure), input_hash);
+
+    let mut command = Command::new(match env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => return failed(Failure::Crashed(format!("can't find the runner: {err}"))),
+    });
+    command
+        .args(["solve", &day.to_string(), &part.to_string(), "--input-path"])
+        .arg(input_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(memory_limit_mb) = memory_limit_mb {
+        command.args(["--memory-limit", &memory_limit_mb.to_string()]);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => return failed(Failure::Crashed(format!("can't start the runner: {err}"))),
+    };
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() > deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return failed(Failure::Timeout(deadline));
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(err) => return failed(Failure::Crashed(err.to_string())),
+        }
+    };
+
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    if let Some(out) = &mut child.stdout {
+        let _ = out.read_to_string(&mut stdout);
+    }
+    if let Some(err) = &mut child.stderr {
+        let _ = err.read_to_string(&mut stderr);
+    }
+    match serde_json::from_str(stdout.trim()) {
+        Ok(record) if status.success() => record,
+        // e.g. the allocator aborting once the memory limit's reached
+        _ => failed(Failure::Crashed(match stderr.lines().next() {
+            Some(reason) => format!("{status}: {reason}"),
+            None => status.to_string(),
+        })),
+    }
+}
+
+/// Keeps this process from using more than `megabytes` of address space
+#[cfg(unix)]
+pub fn limit_memory(megabytes: u64) -> std::io::Result<()> {
+    let bytes = megabytes.saturating_mul(1 << 20);
+    rlimit::setrlimit(rlimit::Resource::AS, bytes, bytes)
+}
+
+#[cfg(not(unix))]
+pub fn limit_memory(_megabytes: u64) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "memory limits are only supported on unix",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Never finishes part 1, solves part 2 right away
+    static STUCK: Day = Day {
+        num: 0,
+        solve: |_, parts, on_solved| {
+            for &part in parts {
+                if part == 1 {
+                    loop {
+                        thread::park();
+                    }
+                }
+                on_solved(Solved {
+                    part,
+                    answer: Ok("2".to_owned()),
+                    parse_time: Some(Duration::ZERO),
+                    solve_time: Some(Duration::ZERO),
+                });
+            }
+        },
+    };
+
+    #[test]
+    fn gives_up_on_slow_parts_and_carries_on() {
+        let deadline = Duration::from_millis(10);
+        let solved = solve_in_thread(&STUCK, &"".into(), &[1, 2], deadline);
+        assert_eq!(solved.len(), 2);
+        assert_eq!(solved[0].answer, Err(Failure::Timeout(deadline)));
+        assert_eq!(solved[1].answer, Ok("2".to_owned()));
+
+        let solved = solve_in_thread(
+            Day::get(6).unwrap(),
+            &"abcd".into(),
+            &[1, 2],
+            Duration::from_mins(1),
+        );
+        assert_eq!(solved[0].answer, Ok("4".to_owned()));
+        assert!(matches!(solved[1].answer, Err(Failure::Error(_))));
+    }
+}
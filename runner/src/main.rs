@@ -0,0 +1,422 @@
+use std::{
+    fmt::Write as _,
+    fs, io, panic,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::{Args, Parser, Subcommand};
+use sha2::{Digest, Sha256};
+
+use compare::Thresholds;
+use days::{Day, DAYS, PARTS};
+use history::{Run, Setup};
+use record::{Format, Record, RecordWriter, Status};
+
+mod compare;
+mod days;
+mod history;
+mod limits;
+mod record;
+mod summary;
+
+const HISTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../aoc-history.jsonl");
+
+/// Solves the Advent of Code 2022 puzzles on their inputs
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve the given days, or every day if none are given
+    Run(RunArgs),
+    /// Solve every part of every day, the days in parallel and each part in a child process of
+    /// its own, and sum up how it went
+    All(AllArgs),
+    /// Compare the latest run's timings to the run before it, or to a baseline, failing if
+    /// any part got slower than allowed
+    Compare(CompareArgs),
+    /// Solve one part on one input and print its record as JSON, what each child process of
+    /// `run --isolate` and `all` runs
+    #[command(hide = true)]
+    Solve(SolveArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// The days to solve
+    days: Vec<u8>,
+    /// Only solve this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+    /// Solve each part in a child process of its own, so one that's given up on is killed
+    /// rather than left running in the background. Use it for timings that get compared
+    #[arg(long)]
+    isolate: bool,
+    /// How many megabytes of memory each child process may use
+    #[arg(long, requires = "isolate")]
+    memory_limit: Option<u64>,
+    #[command(flatten)]
+    options: Options,
+}
+
+impl RunArgs {
+    fn isolation(&self) -> Isolation {
+        if self.isolate {
+            Isolation::Children {
+                memory_limit: self.memory_limit,
+            }
+        } else {
+            Isolation::InProcess
+        }
+    }
+}
+
+#[derive(Args)]
+struct AllArgs {
+    /// Solve the parts on threads of this process instead. One that's given up on keeps
+    /// running in the background then, with nothing to stop it using up all the memory
+    #[arg(long, conflicts_with = "memory_limit")]
+    in_process: bool,
+    /// How many megabytes of memory each child process may use
+    #[arg(long, default_value_t = 4096)]
+    memory_limit: u64,
+    #[command(flatten)]
+    options: Options,
+}
+
+impl AllArgs {
+    fn isolation(&self) -> Isolation {
+        if self.in_process {
+            Isolation::InProcess
+        } else {
+            Isolation::Children {
+                memory_limit: Some(self.memory_limit),
+            }
+        }
+    }
+}
+
+/// Where the parts get solved
+#[derive(Clone, Copy)]
+enum Isolation {
+    /// On threads of the runner, where a part that's given up on keeps running
+    InProcess,
+    /// Each in a child process of its own, limited to `memory_limit` megabytes if given
+    Children { memory_limit: Option<u64> },
+}
+
+/// What the solutions are run on, and how
+#[derive(Args)]
+struct Options {
+    /// Where the days' crates are, each day's inputs are in its crate's `inputs/`
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/.."))]
+    workspace: PathBuf,
+    /// The name of the input file in each day's `inputs/`
+    #[arg(long, default_value = "real.txt")]
+    input: String,
+    /// How many seconds a part may take before it's given up on. Only a child process gets
+    /// killed then: a part solved in process keeps running in the background, slowing down the
+    /// ones after it and using memory without a limit, so no timeout is safe without child
+    /// processes, this default included
+    #[arg(long, default_value = "60", value_parser = seconds)]
+    timeout: Duration,
+    /// The file each run gets appended to, along with the commit it was run at
+    #[arg(long, default_value = HISTORY)]
+    history: PathBuf,
+    /// Don't append this run to the history
+    #[arg(long)]
+    no_history: bool,
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    /// Compare to the latest run at this commit, given as a hash or a prefix of one. Only runs
+    /// solved the same way, by the same command with as many threads, are compared to
+    #[arg(long)]
+    baseline: Option<String>,
+    /// By how many percent a part may get slower
+    #[arg(long, default_value_t = 10.)]
+    threshold: f64,
+    /// Parts that took fewer milliseconds than this in both runs never count as slower
+    #[arg(long, default_value = "1", value_parser = milliseconds)]
+    min_time: Duration,
+    #[arg(long, default_value = HISTORY)]
+    history: PathBuf,
+}
+
+#[derive(Args)]
+struct SolveArgs {
+    day: u8,
+    part: u8,
+    #[arg(long)]
+    input_path: PathBuf,
+    #[arg(long)]
+    memory_limit: Option<u64>,
+}
+
+fn seconds(arg: &str) -> Result<Duration, String> {
+    let seconds: f64 = arg.parse().map_err(|err| format!("{err}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| format!("{err}"))
+}
+
+fn milliseconds(arg: &str) -> Result<Duration, String> {
+    let milliseconds: f64 = arg.parse().map_err(|err| format!("{err}"))?;
+    Duration::try_from_secs_f64(milliseconds / 1000.).map_err(|err| format!("{err}"))
+}
+
+impl Options {
+    fn input_path(&self, day: u8) -> PathBuf {
+        self.workspace
+            .join(format!("d{day:02}"))
+            .join("inputs")
+            .join(&self.input)
+    }
+
+    /// Appends `records` to the history, `parallel` if the days were solved in parallel
+    fn save_to_history(
+        &self,
+        parallel: bool,
+        isolation: Isolation,
+        records: Vec<Record>,
+    ) -> io::Result<()> {
+        if self.no_history {
+            return Ok(());
+        }
+        let setup = Setup::new(parallel, isolation.is_isolated());
+        history::append(&self.history, &Run::new(&self.workspace, setup, records))
+    }
+}
+
+impl Isolation {
+    fn is_isolated(self) -> bool {
+        matches!(self, Self::Children { .. })
+    }
+
+    /// Points out that the timings after a part that was given up on are off, unless it was killed
+    fn warn_about_timeouts(self, records: &[Record]) {
+        if !self.is_isolated()
+            && records
+                .iter()
+                .any(|record| record.status == Status::Timeout)
+        {
+            eprintln!(
+                "the parts given up on are still running, slowing down the ones solved after \
+                 them; solve them in child processes for timings"
+            );
+        }
+    }
+}
+
+/// SHA-256 of `input`, in hex
+fn input_hash(input: &str) -> String {
+    Sha256::digest(input)
+        .iter()
+        .fold(String::new(), |mut hash, byte| {
+            write!(hash, "{byte:02x}").expect("writing to a string");
+            hash
+        })
+}
+
+fn read_input(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// A record per part of `day` asked for, solved on the input at `options.input_path`
+fn run_day(
+    day: &'static Day,
+    parts: &[u8],
+    options: &Options,
+    isolation: Isolation,
+) -> Vec<Record> {
+    let path = options.input_path(day.num);
+    let input = match read_input(&path) {
+        Ok(input) => input,
+        Err(reason) => {
+            return parts
+                .iter()
+                .map(|&part| Record::unreadable_input(day.num, part, reason.clone()))
+                .collect()
+        }
+    };
+    let hash = input_hash(&input);
+    match isolation {
+        Isolation::Children { memory_limit } => parts
+            .iter()
+            .map(|&part| {
+                limits::solve_in_child(day.num, part, &path, &hash, options.timeout, memory_limit)
+            })
+            .collect(),
+        Isolation::InProcess => limits::solve_in_thread(day, &input.into(), parts, options.timeout)
+            .into_iter()
+            .map(|solved| Record::new(day.num, solved, &hash))
+            .collect(),
+    }
+}
+
+fn run(args: &RunArgs) -> io::Result<ExitCode> {
+    let days = if args.days.is_empty() {
+        DAYS.iter().collect()
+    } else {
+        let mut days = Vec::new();
+        for &num in &args.days {
+            let Some(day) = Day::get(num) else {
+                eprintln!("there's no solution for day {num}");
+                return Ok(ExitCode::FAILURE);
+            };
+            days.push(day);
+        }
+        days
+    };
+    let parts = args.part.map_or(PARTS.to_vec(), |part| vec![part]);
+
+    let isolation = args.isolation();
+    let mut writer = RecordWriter::new(args.format, io::stdout().lock())?;
+    let mut records = Vec::new();
+    for day in days {
+        for record in run_day(day, &parts, &args.options, isolation) {
+            writer.write(&record)?;
+            records.push(record);
+        }
+    }
+    isolation.warn_about_timeouts(&records);
+    let all_ok = records.iter().all(|record| record.status == Status::Ok);
+    args.options.save_to_history(false, isolation, records)?;
+    Ok(if all_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// A record per part of each of `days`, the days solved in parallel. Each gets a thread of its
+/// own rather than one of rayon's, which waiting on a day would keep from the solutions using it
+fn run_days(days: &[&'static Day], options: &Options, isolation: Isolation) -> Vec<Record> {
+    thread::scope(|scope| {
+        let days: Vec<_> = days
+            .iter()
+            .map(|day| scope.spawn(move || run_day(day, &PARTS, options, isolation)))
+            .collect();
+        days.into_iter()
+            .flat_map(|day| {
+                day.join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect()
+    })
+}
+
+fn all(args: &AllArgs) -> io::Result<ExitCode> {
+    let isolation = args.isolation();
+    let start = Instant::now();
+    let records = run_days(&DAYS.iter().collect::<Vec<_>>(), &args.options, isolation);
+    print!("{}", summary::table(&records, start.elapsed()));
+    isolation.warn_about_timeouts(&records);
+    let all_ok = records.iter().all(|record| record.status == Status::Ok);
+    args.options.save_to_history(true, isolation, records)?;
+    Ok(if all_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn compare(args: &CompareArgs) -> io::Result<ExitCode> {
+    let runs = history::load(&args.history)?;
+    let Some((latest, earlier)) = runs.split_last() else {
+        eprintln!("{} has no runs in it", args.history.display());
+        return Ok(ExitCode::FAILURE);
+    };
+    if !latest.has_reliable_timings() {
+        eprintln!("the latest run gave up on parts that kept running, its timings are off");
+    }
+    let Some(baseline) = compare::baseline(earlier, latest, args.baseline.as_deref()) else {
+        eprintln!(
+            "there's no earlier run to compare to, solved the same way as the latest one and \
+             without parts left running in the background"
+        );
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let thresholds = Thresholds {
+        max_regression_percent: args.threshold,
+        min_duration: args.min_time,
+    };
+    let (report, regressed) = compare::report(baseline, latest, &thresholds);
+    print!("{report}");
+    Ok(if regressed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn solve(args: &SolveArgs) -> io::Result<ExitCode> {
+    if let Some(memory_limit) = args.memory_limit {
+        limits::limit_memory(memory_limit)?;
+    }
+    let Some(day) = Day::get(args.day) else {
+        eprintln!("there's no solution for day {}", args.day);
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let record = match read_input(&args.input_path) {
+        Ok(input) => {
+            let hash = input_hash(&input);
+            let mut record = None;
+            (day.solve)(&input, &[args.part], &mut |solved| {
+                record = Some(Record::new(day.num, solved, &hash));
+            });
+            record.expect("every part asked for gets solved")
+        }
+        Err(reason) => Record::unreadable_input(day.num, args.part, reason),
+    };
+    RecordWriter::new(Format::Json, io::stdout().lock())?.write(&record)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> io::Result<ExitCode> {
+    match Cli::parse().command {
+        Command::Run(args) => run(&args),
+        Command::All(args) => all(&args),
+        Command::Compare(args) => compare(&args),
+        Command::Solve(args) => solve(&args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_in_parallel_leave_rayon_to_the_solutions() {
+        // in process, the test binary can't solve a part in a child process
+        let cli = Cli::try_parse_from([
+            "aoc",
+            "all",
+            "--in-process",
+            "--input",
+            "test.txt",
+            "--timeout",
+            "10",
+        ]);
+        let Ok(Cli {
+            command: Command::All(args),
+        }) = cli
+        else {
+            panic!("`all` takes these options");
+        };
+        // more days than rayon has threads, each solved in parallel by rayon
+        let days = vec![Day::get(15).unwrap(); rayon::current_num_threads() + 1];
+        let records = run_days(&days, &args.options, args.isolation());
+        assert_eq!(records.len(), days.len() * PARTS.len());
+        assert!(records.iter().all(|record| record.status == Status::Ok));
+    }
+}
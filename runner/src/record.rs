@@ -0,0 +1,248 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::days::{Failure, Solved};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+    /// The solution is a `todo!()` or `unimplemented!()`
+    Unimplemented,
+    Panicked,
+    /// The solution took longer than it was allowed to
+    Timeout,
+    /// The child process running the solution died, see [`Failure::Crashed`]
+    Crashed,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::Unimplemented => "unimplemented",
+            Self::Panicked => "panicked",
+            Self::Timeout => "timeout",
+            Self::Crashed => "crashed",
+        }
+    }
+}
+
+/// What running one part of a day came to, one line of output
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+    pub answer: Option<String>,
+    /// What went wrong, unless the status is `ok`
+    pub error: Option<String>,
+    pub parse_ns: Option<u64>,
+    pub solve_ns: Option<u64>,
+    /// SHA-256 of the input, in hex
+    pub input_hash: Option<String>,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl Record {
+    pub fn new(day: u8, solved: Solved, input_hash: &str) -> Self {
+        let (status, answer, error) = match solved.answer {
+            Ok(answer) => (Status::Ok, Some(answer), None),
+            Err(Failure::Error(err)) => (Status::Error, None, Some(err)),
+            // the messages `todo!()` and `unimplemented!()` start with
+            Err(Failure::Panic(message))
+                if message.starts_with("not yet implemented")
+                    || message.starts_with("not implemented") =>
+            {
+                (Status::Unimplemented, None, Some(message))
+            }
+            Err(Failure::Panic(message)) => (Status::Panicked, None, Some(message)),
+            Err(Failure::Timeout(deadline)) => (
+                Status::Timeout,
+                None,
+                Some(format!("gave up after {deadline:?}")),
+            ),
+            Err(Failure::Crashed(reason)) => (Status::Crashed, None, Some(reason)),
+        };
+        Self {
+            day,
+            part: solved.part,
+            status,
+            answer,
+            error,
+            parse_ns: solved.parse_time.map(nanos),
+            solve_ns: solved.solve_time.map(nanos),
+            input_hash: Some(input_hash.to_owned()),
+        }
+    }
+
+    /// For a part that couldn't be run, because its input couldn't be read
+    pub fn unreadable_input(day: u8, part: u8, reason: String) -> Self {
+        Self {
+            day,
+            part,
+            status: Status::Error,
+            answer: None,
+            error: Some(reason),
+            parse_ns: None,
+            solve_ns: None,
+            input_hash: None,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Format {
+    /// A line per part, for reading
+    #[default]
+    Human,
+    /// A JSON object per line
+    Json,
+    /// CSV with a header row
+    Csv,
+}
+
+const CSV_HEADER: &str = "day,part,status,answer,parse_ns,solve_ns,input_hash,error";
+
+/// `field` quoted if it has to be
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn csv_line(record: &Record) -> String {
+    let optional = |field: Option<u64>| field.map(|field| field.to_string()).unwrap_or_default();
+    let mut line = format!("{},{},{}", record.day, record.part, record.status.as_str());
+    for field in [
+        record.answer.as_deref().map(csv_field).unwrap_or_default(),
+        optional(record.parse_ns),
+        optional(record.solve_ns),
+        record.input_hash.clone().unwrap_or_default(),
+        record.error.as_deref().map(csv_field).unwrap_or_default(),
+    ] {
+        write!(line, ",{field}").expect("writing to a string");
+    }
+    line
+}
+
+fn human_line(record: &Record) -> String {
+    let mut line = format!("day {:>2} part {}: ", record.day, record.part);
+    match (&record.answer, &record.error) {
+        (Some(answer), _) => line.push_str(answer),
+        (None, error) => write!(
+            line,
+            "{} ({})",
+            record.status.as_str(),
+            error.as_deref().unwrap_or_default()
+        )
+        .expect("writing to a string"),
+    }
+    if let (Some(parse_ns), Some(solve_ns)) = (record.parse_ns, record.solve_ns) {
+        write!(
+            line,
+            "  [parse {:?}, solve {:?}]",
+            Duration::from_nanos(parse_ns),
+            Duration::from_nanos(solve_ns)
+        )
+        .expect("writing to a string");
+    }
+    line
+}
+
+/// Writes records out one at a time in `format`, so they show up as soon as they're ready
+pub struct RecordWriter<W: Write> {
+    format: Format,
+    out: W,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(format: Format, mut out: W) -> io::Result<Self> {
+        if format == Format::Csv {
+            writeln!(out, "{CSV_HEADER}")?;
+        }
+        Ok(Self { format, out })
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let line = match self.format {
+            Format::Human => human_line(record),
+            Format::Json => serde_json::to_string(record)?,
+            Format::Csv => csv_line(record),
+        };
+        writeln!(self.out, "{line}")?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(answer: Result<&str, Failure>) -> Solved {
+        Solved {
+            part: 1,
+            answer: answer.map(str::to_owned),
+            parse_time: Some(Duration::from_micros(3)),
+            solve_time: Some(Duration::from_micros(5)),
+        }
+    }
+
+    #[test]
+    fn statuses() {
+        let status = |answer| Record::new(22, solved(answer), "").status;
+        assert_eq!(status(Ok("42")), Status::Ok);
+        assert_eq!(status(Err(Failure::Error("no".to_owned()))), Status::Error);
+        assert_eq!(
+            status(Err(Failure::Panic("not yet implemented".to_owned()))),
+            Status::Unimplemented
+        );
+        assert_eq!(
+            status(Err(Failure::Panic("index out of bounds".to_owned()))),
+            Status::Panicked
+        );
+        assert_eq!(
+            status(Err(Failure::Timeout(Duration::from_secs(1)))),
+            Status::Timeout
+        );
+    }
+
+    #[test]
+    fn formats() {
+        let record = Record::new(1, solved(Ok("a,\"b\"")), "ab12");
+        let write = |format| {
+            let mut out = Vec::new();
+            RecordWriter::new(format, &mut out)
+                .unwrap()
+                .write(&record)
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(
+            write(Format::Csv),
+            format!("{CSV_HEADER}\n1,1,ok,\"a,\"\"b\"\"\",3000,5000,ab12,\n")
+        );
+        assert_eq!(
+            write(Format::Json),
+            "{\"day\":1,\"part\":1,\"status\":\"ok\",\"answer\":\"a,\\\"b\\\"\",\"error\":null,\
+             \"parse_ns\":3000,\"solve_ns\":5000,\"input_hash\":\"ab12\"}\n"
+        );
+        assert_eq!(
+            write(Format::Human),
+            "day  1 part 1: a,\"b\"  [parse 3µs, solve 5µs]\n"
+        );
+    }
+}
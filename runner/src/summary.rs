@@ -0,0 +1,124 @@
+use std::{fmt::Write as _, time::Duration};
+
+use crate::record::{Record, Status};
+
+const HEADER: [&str; 6] = ["day", "part", "status", "answer", "parse", "solve"];
+
+pub fn duration(nanos: Option<u64>) -> String {
+    nanos.map_or_else(
+        || "-".to_owned(),
+        |nanos| format!("{:.2?}", Duration::from_nanos(nanos)),
+    )
+}
+
+/// `rows` under `header`, with each column as wide as its widest cell.
+/// The `right_aligned` columns are lined up on the right, like numbers
+pub fn columns<const N: usize>(
+    header: [&str; N],
+    right_aligned: [bool; N],
+    rows: &[[String; N]],
+) -> String {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut res = String::new();
+    for row in [header.map(str::to_owned)].iter().chain(rows) {
+        let mut line = String::new();
+        for ((cell, width), right_aligned) in row.iter().zip(widths).zip(right_aligned) {
+            if right_aligned {
+                write!(line, "{cell:>width$}  ")
+            } else {
+                write!(line, "{cell:<width$}  ")
+            }
+            .expect("writing to a string");
+        }
+        res.push_str(line.trim_end());
+        res.push('\n');
+    }
+    res
+}
+
+/// The records as a table, in the order given, then how long it all took and what went wrong
+pub fn table(records: &[Record], wall_time: Duration) -> String {
+    let rows: Vec<[String; 6]> = records
+        .iter()
+        .map(|record| {
+            [
+                record.day.to_string(),
+                record.part.to_string(),
+                record.status.as_str().to_owned(),
+                record.answer.clone().unwrap_or_default(),
+                duration(record.parse_ns),
+                duration(record.solve_ns),
+            ]
+        })
+        .collect();
+    let mut res = columns(HEADER, [true, true, false, false, true, true], &rows);
+
+    let num_ok = records
+        .iter()
+        .filter(|record| record.status == Status::Ok)
+        .count();
+    let solving: u64 = records.iter().filter_map(|record| record.solve_ns).sum();
+    writeln!(
+        res,
+        "\n{num_ok} of {} parts solved, {:.2?} spent solving, {wall_time:.2?} in total",
+        records.len(),
+        Duration::from_nanos(solving)
+    )
+    .expect("writing to a string");
+    for record in records.iter().filter(|record| record.status != Status::Ok) {
+        writeln!(
+            res,
+            "day {} part {}: {} ({})",
+            record.day,
+            record.part,
+            record.status.as_str(),
+            record.error.as_deref().unwrap_or_default()
+        )
+        .expect("writing to a string");
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::{Failure, Solved};
+
+    #[test]
+    fn table_with_failures() {
+        let solved = |part, answer| Solved {
+            part,
+            answer,
+            parse_time: Some(Duration::from_micros(20)),
+            solve_time: Some(Duration::from_millis(3)),
+        };
+        let records = [
+            Record::new(9, solved(1, Ok("13".to_owned())), ""),
+            Record::new(
+                9,
+                solved(2, Err(Failure::Panic("not yet implemented".to_owned()))),
+                "",
+            ),
+            Record::unreadable_input(10, 1, "d10/inputs/real.txt: not found".to_owned()),
+        ];
+        assert_eq!(
+            table(&records, Duration::from_millis(10)),
+            "\
+day  part  status         answer    parse   solve
+  9     1  ok             13      20.00µs  3.00ms
+  9     2  unimplemented          20.00µs  3.00ms
+ 10     1  error                        -       -
+
+1 of 3 parts solved, 6.00ms spent solving, 10.00ms in total
+day 9 part 2: unimplemented (not yet implemented)
+day 10 part 1: error (d10/inputs/real.txt: not found)
+"
+        );
+    }
+}
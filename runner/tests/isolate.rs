@@ -0,0 +1,37 @@
+use std::{fs, process::Command};
+
+/// The example jet pattern, on which day 17 part 2 never finishes
+const JETS: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>\n";
+
+#[test]
+fn a_part_that_never_finishes_gets_stopped() {
+    let workspace = std::env::temp_dir().join(format!("aoc-isolate-{}", std::process::id()));
+    let inputs = workspace.join("d17").join("inputs");
+    fs::create_dir_all(&inputs).unwrap();
+    fs::write(inputs.join("test.txt"), JETS).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args([
+            "run", "17", "--part", "2", "--format", "json", "--input", "test.txt",
+        ])
+        .args([
+            "--isolate",
+            "--memory-limit",
+            "256",
+            "--timeout",
+            "5",
+            "--no-history",
+        ])
+        .arg("--workspace")
+        .arg(&workspace)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&workspace).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let record: serde_json::Value = serde_json::from_str(stdout.trim()).unwrap();
+    assert!(
+        matches!(record["status"].as_str(), Some("timeout" | "crashed")),
+        "{record}"
+    );
+}